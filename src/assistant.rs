@@ -0,0 +1,308 @@
+/*
+    Solve assistant module.
+
+    Helps with a Mastermind game played somewhere else (a physical board, a
+    website): the player enters each guess with the feedback it got, whoever
+    chose the guess, and the assistant works out what is left.
+
+    Public API:
+    - SolveAssistant::new: an empty board for a rule set and bot settings.
+    - SolveAssistant::with_space: the same for a CodeSpace, e.g. one without
+      repeated colors.
+    - SolveAssistant::add_answer / undo / answers: edit and read the board.
+    - SolveAssistant::remaining: the codes that fit every answer, or just
+      their count when there are more than a given limit.
+    - SolveAssistant::recommend: the bot's next guess for the board.
+    - SolveAssistant::evaluate: how a guess the player is considering would
+      split the remaining codes.
+    - recommend_guess (crate only): the bot's guess for any list of answers,
+      shared with the hints module.
+
+    Public types:
+    - AnswerError: reasons an entered answer is refused.
+    - Remaining: the listed codes, their count, or TooMany to count.
+    - GuessEvaluation: the partition a guess makes of the remaining codes.
+
+    Notes:
+    - Feedback follows check_for_matches; codes are scored with
+      CodeSpace::score, which gives the same answer without building flag
+      pegs and hides what the space's feedback rule doesn't reveal.
+    - Listing, counting and evaluating walk the whole search space, so they are
+      only done for spaces up to MAX_ENUMERATED_SPACE codes. The recommendation
+      works at any size, since larger games get the sampling bot.
+    - Answers are black and white counts; a space under the positional rule
+      is treated as a standard one.
+    - Answers no code could give together are refused with the answers that
+      must hold the mistake (see the humanscore module).
+*/
+
+use crate::{
+    bot::{BotSettings, Difficulty},
+    engine::build_bot,
+    gamelogic::{find_repeated_color, flags_from_feedback, is_possible_feedback},
+    humanscore::find_suspects,
+    sampling::MAX_ENUMERATED_SPACE,
+    strategy::SearchControl,
+    types::{Code, CodeSpace, Color, Feedback, FeedbackRule, Line},
+};
+
+use rayon::prelude::*;
+use std::cmp::Reverse;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerError {
+    WrongLength { expected: usize, found: usize },
+    ColorNotInPlay(Color),
+    RepeatedColor(Color),
+    ImpossibleFeedback(Feedback),
+    // Guess numbers (1-based) whose answer alone must be the mistake; empty
+    // if it takes two or more.
+    Inconsistent { suspects: Vec<usize> },
+}
+
+impl std::fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AnswerError::WrongLength { expected, found } => {
+                write!(f, "Expected {} pegs, got {}.", expected, found)
+            }
+            AnswerError::ColorNotInPlay(color) => write!(f, "{:?} is not in play.", color),
+            AnswerError::RepeatedColor(color) => {
+                write!(
+                    f,
+                    "{:?} is used more than once; colors can't repeat.",
+                    color
+                )
+            }
+            AnswerError::ImpossibleFeedback(feedback) => write!(
+                f,
+                "{} black and {} white can't happen.",
+                feedback.correct_position, feedback.correct_color
+            ),
+            AnswerError::Inconsistent { suspects } => match suspects.as_slice() {
+                [] => write!(
+                    f,
+                    "No code fits these answers; more than one must be wrong."
+                ),
+                [number] => write!(
+                    f,
+                    "No code fits these answers; the answer to guess {} must be wrong.",
+                    number
+                ),
+                numbers => {
+                    let numbers: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+                    write!(
+                        f,
+                        "No code fits these answers; one of the answers to guesses {} is wrong.",
+                        numbers.join(", ")
+                    )
+                }
+            },
+        }
+    }
+}
+
+pub enum Remaining {
+    Codes(Vec<Line>),
+    Count(u64),
+    TooMany,
+}
+
+pub struct GuessEvaluation {
+    // Whether the guess itself fits every answer (and so could win).
+    pub is_consistent: bool,
+    pub remaining: u64,
+    // Every possible answer to the guess with the number of codes giving it,
+    // largest first.
+    pub parts: Vec<(Feedback, u64)>,
+    pub worst_case: u64,
+    pub expected_size: f64,
+    // The bot's heuristic cost of the guess (lower is better).
+    pub cost: u64,
+}
+
+pub struct SolveAssistant {
+    space: CodeSpace,
+    settings: BotSettings,
+    answers: Vec<(Line, Feedback)>,
+}
+
+impl SolveAssistant {
+    pub fn new(palette: Vec<Color>, no_of_pegs: usize, settings: BotSettings) -> Self {
+        Self::with_space(CodeSpace::new(palette, no_of_pegs), settings)
+    }
+
+    pub fn with_space(space: CodeSpace, settings: BotSettings) -> Self {
+        // Answers are entered as counts, which the positional rule shares
+        // with the standard one.
+        let space = match space.feedback_rule {
+            FeedbackRule::Positional => space.with_feedback_rule(FeedbackRule::Standard),
+            _ => space,
+        };
+        SolveAssistant {
+            space,
+            settings,
+            answers: Vec::new(),
+        }
+    }
+
+    pub fn answers(&self) -> &[(Line, Feedback)] {
+        &self.answers
+    }
+
+    pub fn add_answer(&mut self, guess: Line, feedback: Feedback) -> Result<(), AnswerError> {
+        let width = self.space.width;
+        if guess.pegs.len() != width {
+            return Err(AnswerError::WrongLength {
+                expected: width,
+                found: guess.pegs.len(),
+            });
+        }
+        if let Some(peg) = guess
+            .pegs
+            .iter()
+            .find(|peg| self.space.index_of(peg.color).is_none())
+        {
+            return Err(AnswerError::ColorNotInPlay(peg.color));
+        }
+        if self.space.no_repeats
+            && let Some(color) = find_repeated_color(&guess)
+        {
+            return Err(AnswerError::RepeatedColor(color));
+        }
+        if !is_possible_feedback(feedback, width, self.space.feedback_rule) {
+            return Err(AnswerError::ImpossibleFeedback(feedback));
+        }
+
+        self.answers.push((guess, feedback));
+        if let Some(suspects) = find_suspects(&self.space, &self.answers) {
+            self.answers.pop();
+            return Err(AnswerError::Inconsistent {
+                suspects: suspects.iter().map(|index| index + 1).collect(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<(Line, Feedback)> {
+        self.answers.pop()
+    }
+
+    pub fn remaining(&self, list_limit: usize) -> Remaining {
+        let Some(codes) = self.consistent_codes() else {
+            return Remaining::TooMany;
+        };
+        if codes.len() > list_limit {
+            return Remaining::Count(codes.len() as u64);
+        }
+        Remaining::Codes(codes.iter().map(|code| self.space.unrank(*code)).collect())
+    }
+
+    pub fn recommend(&self, control: &SearchControl) -> Line {
+        let width = self.space.width;
+        let answers: Vec<(Line, Line)> = self
+            .answers
+            .iter()
+            .map(|(guess, feedback)| (guess.clone(), flags_from_feedback(*feedback, width)))
+            .collect();
+        recommend_guess(self.settings, self.space.clone(), &answers, control)
+    }
+
+    // None for a guess of the wrong shape or when the space is too large.
+    pub fn evaluate(&self, guess: &Line) -> Option<GuessEvaluation> {
+        let width = self.space.width;
+        if guess.pegs.len() != width
+            || guess
+                .pegs
+                .iter()
+                .any(|peg| self.space.index_of(peg.color).is_none())
+        {
+            return None;
+        }
+        let codes = self.consistent_codes()?;
+        let guess_code = self.space.rank(guess);
+        let guess_digits = self.digits(guess_code);
+
+        let mut counts = vec![0u64; (width + 1) * (width + 1)];
+        for code in &codes {
+            let feedback = self.space.score(&self.digits(*code), &guess_digits);
+            counts[feedback.encode(width)] += 1;
+        }
+        let total = codes.len() as u64;
+        let mut parts: Vec<(Feedback, u64)> = counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(encoded, count)| (Feedback::decode(encoded, width), *count))
+            .collect();
+        parts.sort_by_key(|(_, count)| Reverse(*count));
+        let sizes: Vec<usize> = counts.iter().map(|count| *count as usize).collect();
+
+        Some(GuessEvaluation {
+            is_consistent: codes.binary_search(&guess_code).is_ok(),
+            remaining: total,
+            worst_case: parts.first().map_or(0, |(_, count)| *count),
+            expected_size: match total {
+                0 => 0.0,
+                _ => {
+                    counts
+                        .iter()
+                        .map(|count| (count * count) as f64)
+                        .sum::<f64>()
+                        / total as f64
+                }
+            },
+            cost: self.settings.heuristic.cost(&sizes),
+            parts,
+        })
+    }
+
+    // Every code that fits all answers, in ascending order.
+    fn consistent_codes(&self) -> Option<Vec<Code>> {
+        self.space
+            .count()
+            .filter(|count| *count <= MAX_ENUMERATED_SPACE)?;
+        let answers: Vec<(Vec<u8>, Feedback)> = self
+            .answers
+            .iter()
+            .map(|(guess, feedback)| (self.digits(self.space.rank(guess)), *feedback))
+            .collect();
+        Some(
+            self.space
+                .codes()
+                .into_par_iter()
+                .filter(|code| {
+                    let digits = self.digits(*code);
+                    answers
+                        .iter()
+                        .all(|(guess, feedback)| self.space.score(&digits, guess) == *feedback)
+                })
+                .collect(),
+        )
+    }
+
+    fn digits(&self, code: Code) -> Vec<u8> {
+        let mut digits = vec![0; self.space.width];
+        self.space.digits(code, &mut digits);
+        digits
+    }
+}
+
+// A fresh full-strength bot replaying the answers (each guess with its flag
+// pegs), asked for its next guess.
+pub(crate) fn recommend_guess(
+    settings: BotSettings,
+    space: CodeSpace,
+    answers: &[(Line, Line)],
+    control: &SearchControl,
+) -> Line {
+    let settings = BotSettings {
+        difficulty: Difficulty::Full,
+        ..settings
+    };
+    let mut bot = build_bot(settings, space);
+    for (guess, flags) in answers {
+        bot.observe_flags(guess, flags);
+    }
+    bot.next_guess_within(control)
+}
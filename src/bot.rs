@@ -1,230 +1,852 @@
 /*
     Bot module.
 
-    Provides the Bot type and the public functions used by the game loop to make
-    the bot perform guesses and reset between rounds.
+    Provides the Bot type used by the engine to make the bot perform guesses,
+    learn from feedback and reset between rounds.
 
     Responsibilities:
     - Holding all internal bot state (possible solutions, guesses, feedback).
     - Computing valid solution space and reducing it after each guess.
     - Producing first guesses and educated guesses using a minimax-style strategy.
-    - Integrating bot behavior into the main game loop.
 
     Public API:
-    - Bot::new: construct a new bot for a given game configuration.
-    - reset_bot_for_new_round: clear bot state at the start of a new round.
-    - handle_bot_input: make a single bot guess and update the game state.
-    - bot_guess: convenience wrapper for triggering one bot action.
+    - BotMode / Heuristic / Difficulty / BotSettings: per-bot options, stored in
+      GameConfig and Gamestate.
+    - parse_bot_mode / parse_heuristic / parse_difficulty: parse config/manual setup values.
+    - Heuristic::cost / cost_with_lies: score a partition of the remaining solutions.
+    - Bot::new / with_space: construct a new bot for a game or a CodeSpace.
+    - Bot::next_guess / next_guess_within: pick the next guess to play.
+    - Bot::observe_feedback / observe_flags / observe_answer: prune the search space.
+    - Bot::largest_partition / largest_partition_where / retain_solutions:
+      used by the adversarial and lying Code Makers.
+    - Bot::next_batch_within / final_guess: the two halves of a static round.
+    - Bot::reset_for_new_round: clear bot state at the start of a new round.
+    - RandomBot: the Random difficulty, a BreakerStrategy for any size of game.
 
     Internal logic (private):
-    - populate_set_with_solutions, generate_recursive: build the full search space.
-    - make_first_guess, make_educated_guess: determine guessing strategy.
-    - prune_non_viable_solutions: remove impossible lines after feedback.
-    - reset_possible_solutions: rebuild full state for a new round.
-    - num_to_color: map numeric identifiers to Color.
+    - make_first_guess, make_educated_guess_parallel, make_casual_guess: pick a guess.
+    - partition_counts / possible_answers: how a guess splits the remaining solutions.
+    - prune_non_viable_solutions / count_lies: remove impossible codes after feedback.
+    - make_separating_batch / refined_parts / part_sizes: the batch of a static round.
+
+    Notes:
+    - Candidates are stored, pruned and scored as packed Codes; they are only
+      converted to Lines when a guess leaves the bot or feedback comes in.
+    - The search space must fit in a Code (colors^pegs <= u64::MAX).
+    - Answers come from CodeSpace::answer, so pruning and every heuristic
+      follow the space's rules (no repeats, feedback rule, lies, words).
 */
 
 use crate::{
-    gamelogic::check_for_matches,
-    gamestate::Gamestate,
-    types::{Color, Feedback, Line},
+    feedbacktable::FeedbackTable,
+    gamelogic::flags_from_marks,
+    optimal::OptimalSolver,
+    strategy::{BreakerStrategy, SearchControl},
+    symmetry::Symmetries,
+    types::{Code, CodeSpace, Color, Feedback, FeedbackRule, Line, MAX_CODE_WIDTH},
 };
 
-use rand::{Rng, rng};
+use rand::{Rng, rng, seq::IndexedRandom};
 use rayon::prelude::*;
-use rustc_hash::FxHashMap;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub(crate) const GREEDY_CANDIDATES: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum BotMode {
+    // A random two-color opening, then minimax over the remaining candidates
+    // only (an "imperfect" Knuth).
+    #[default]
+    Classic,
+    // Knuth's algorithm: every code in the space is scored, ties go to
+    // consistent candidates and then to the lowest Code. Plays 1122 first on
+    // 4x6 and never needs more than five guesses there.
+    Knuth,
+    // The fewest guesses on average, found by the OptimalSolver (4.3403 on
+    // 4x6). Spaces over MAX_OPTIMAL_SPACE codes, no repeats, black pegs only
+    // and lies play as Knuth.
+    Optimal,
+    // Played by the SamplingBot, which never enumerates the space; the engine
+    // also seats it for spaces over MAX_ENUMERATED_SPACE codes.
+    Sampling,
+    // Played by the ConstraintBot, which backtracks to a code that fits every
+    // clue; meant for 10-30 pegs.
+    Constraint,
+}
+
+impl std::fmt::Display for BotMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BotMode::Classic => write!(f, "Classic"),
+            BotMode::Knuth => write!(f, "Knuth"),
+            BotMode::Optimal => write!(f, "Optimal"),
+            BotMode::Sampling => write!(f, "Sampling"),
+            BotMode::Constraint => write!(f, "Constraint"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Heuristic {
+    // Smallest worst-case partition (Knuth's rule).
+    #[default]
+    Minimax,
+    // Largest information gain (Shannon entropy of the partition).
+    Entropy,
+    // Smallest expected number of remaining solutions.
+    ExpectedSize,
+    // Largest number of distinct answers.
+    MostParts,
+}
+
+impl Heuristic {
+    // Cost of a guess given how many solutions fall in each feedback partition.
+    // Lower is better for every heuristic, so guesses can be compared directly.
+    pub fn cost(&self, partition_counts: &[usize]) -> u64 {
+        self.cost_with_lies(partition_counts, partition_counts)
+    }
+
+    // The same when lies are allowed: partition_counts are the solutions that
+    // would truly give each answer, survivor_counts the solutions still in
+    // play after it, lies included. An answer comes up as often as it is
+    // true, so ExpectedSize and Entropy weight each answer's survivors by
+    // that; MostParts counts the answers that can come up truly.
+    pub fn cost_with_lies(&self, partition_counts: &[usize], survivor_counts: &[usize]) -> u64 {
+        let weighted = || partition_counts.iter().zip(survivor_counts);
+        match self {
+            Heuristic::Minimax => survivor_counts.iter().copied().max().unwrap_or(0) as u64,
+            // Sum of n^2 is proportional to the expected remaining size sum(n/N * n).
+            Heuristic::ExpectedSize => weighted().map(|(n, s)| (n * s) as u64).sum(),
+            Heuristic::MostParts => {
+                let parts = partition_counts.iter().filter(|n| **n > 0).count();
+                (partition_counts.len() - parts) as u64
+            }
+            // Entropy is log(N) - sum(n * log n) / N, so maximizing it means minimizing
+            // sum(n * log n). Fixed point keeps equal partitions exactly equal.
+            Heuristic::Entropy => {
+                let sum: f64 = weighted()
+                    .filter(|(n, s)| **n > 0 && **s > 1)
+                    .map(|(n, s)| *n as f64 * (*s as f64).ln())
+                    .sum();
+                (sum * 1_000_000.0).round() as u64
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Heuristic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Heuristic::Minimax => write!(f, "Minimax (worst case)"),
+            Heuristic::Entropy => write!(f, "Maximum entropy"),
+            Heuristic::ExpectedSize => write!(f, "Expected size"),
+            Heuristic::MostParts => write!(f, "Most parts"),
+        }
+    }
+}
+
+// Below Full the bot mode is ignored, except that spaces too large to
+// enumerate still get the SamplingBot for Consistent and Greedy.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    // Any random code, ignoring the feedback; played by the RandomBot.
+    Random,
+    // A random code that fits all feedback so far.
+    Consistent,
+    // The code among GREEDY_CANDIDATES random consistent ones that splits the
+    // rest best.
+    Greedy,
+    #[default]
+    Full,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Difficulty::Random => write!(f, "Random guesser"),
+            Difficulty::Consistent => write!(f, "Consistent guesser"),
+            Difficulty::Greedy => write!(f, "Greedy (one step)"),
+            Difficulty::Full => write!(f, "Full strength"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct BotSettings {
+    pub mode: BotMode,
+    #[serde(default)]
+    pub heuristic: Heuristic,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    // Thinking time per guess in milliseconds; None waits for the full search.
+    #[serde(default)]
+    pub time_limit_ms: Option<u64>,
+    // Worker threads for the bot's searches; None uses every core.
+    #[serde(default)]
+    pub threads: Option<usize>,
+}
+
+impl BotSettings {
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit_ms.map(Duration::from_millis)
+    }
+}
+
+pub fn parse_bot_mode(value: &str) -> Option<BotMode> {
+    match value.trim().to_lowercase().as_str() {
+        "c" | "classic" => Some(BotMode::Classic),
+        "k" | "knuth" => Some(BotMode::Knuth),
+        "o" | "optimal" => Some(BotMode::Optimal),
+        "s" | "sampling" => Some(BotMode::Sampling),
+        "n" | "constraint" => Some(BotMode::Constraint),
+        _ => None,
+    }
+}
+
+// The modes a word game's bot can play; the others need every code of the
+// palette, which words aren't.
+pub fn plays_words(mode: BotMode) -> bool {
+    matches!(mode, BotMode::Classic | BotMode::Knuth)
+}
+
+pub fn parse_difficulty(value: &str) -> Option<Difficulty> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "r" | "random" => Some(Difficulty::Random),
+        "2" | "c" | "consistent" => Some(Difficulty::Consistent),
+        "3" | "g" | "greedy" => Some(Difficulty::Greedy),
+        "4" | "f" | "full" | "minimax" => Some(Difficulty::Full),
+        _ => None,
+    }
+}
+
+pub fn parse_heuristic(value: &str) -> Option<Heuristic> {
+    match value.trim().to_lowercase().as_str() {
+        "m" | "minimax" | "worst_case" => Some(Heuristic::Minimax),
+        "e" | "entropy" => Some(Heuristic::Entropy),
+        "x" | "expected" | "expected_size" => Some(Heuristic::ExpectedSize),
+        "p" | "parts" | "most_parts" => Some(Heuristic::MostParts),
+        _ => None,
+    }
+}
+
+// Whether some code gives a guess each answer, by the guess's pattern of
+// repeated colors (see Bot::possible_answers).
+type PossibleAnswers = HashMap<Vec<u8>, Arc<Vec<bool>>>;
 
 #[derive(Clone)]
 pub struct Bot {
-    pub possible_solutions: HashSet<Line>,
-    pub available_colors: Vec<Color>,
-    pub no_of_pegs: usize,
-    pub guessed_lines: Vec<Line>,
-    pub current_feedback: Feedback,
-    pub current_guess: Line,
+    pub settings: BotSettings,
+    pub possible_solutions: Vec<Code>,
+    // How many answers each of possible_solutions would make lies; all 0
+    // unless the space allows lies.
+    pub lie_counts: Vec<u8>,
+    pub space: CodeSpace,
+    pub feedback_table: Option<Arc<FeedbackTable>>,
+    pub optimal_solver: Option<Arc<Mutex<OptimalSolver>>>,
+    pub guessed_codes: Vec<Code>,
+    pub symmetries: Symmetries,
+    // Filled in as lies need it.
+    possible_answers: Arc<Mutex<PossibleAnswers>>,
+    // The answer to current_guess, packed by CodeSpace::answer.
+    pub current_answer: usize,
+    pub current_guess: Code,
     pub is_first_guess: bool,
 }
 
 impl Bot {
-    pub fn new(is_empty_allowed: bool, no_of_pegs: usize) -> Self {
+    pub fn new(available_colors: Vec<Color>, no_of_pegs: usize, settings: BotSettings) -> Self {
+        Self::with_space(CodeSpace::new(available_colors, no_of_pegs), settings)
+    }
+
+    // Small spaces get a FeedbackTable; it and the OptimalSolver are shared
+    // between clones and kept across rounds.
+    pub fn with_space(space: CodeSpace, settings: BotSettings) -> Self {
+        let feedback_table = FeedbackTable::build_answers(&space, |target, guess| {
+            answer_between(&space, target, guess)
+        })
+        .map(Arc::new);
+        let optimal_solver = match (&feedback_table, settings.mode) {
+            (Some(table), BotMode::Optimal)
+                if !space.no_repeats
+                    && space.feedback_rule == FeedbackRule::Standard
+                    && space.max_lies == 0
+                    && space.dictionary.is_none() =>
+            {
+                OptimalSolver::new(space.clone(), Arc::clone(table))
+                    .map(|solver| Arc::new(Mutex::new(solver)))
+            }
+            _ => None,
+        };
+        let possible_solutions = Self::populate_set_with_solutions(&space);
         Bot {
-            possible_solutions: Self::populate_set_with_solutions(is_empty_allowed, no_of_pegs),
-            available_colors: Color::all_colors(),
-            no_of_pegs: no_of_pegs,
-            guessed_lines: Vec::new(),
-            current_feedback: Feedback::empty(),
-            current_guess: Line::empty(no_of_pegs),
+            settings,
+            lie_counts: vec![0; possible_solutions.len()],
+            possible_solutions,
+            feedback_table,
+            optimal_solver,
+            guessed_codes: Vec::new(),
+            symmetries: Symmetries::new(&space),
+            possible_answers: Arc::default(),
+            space,
+            current_answer: 0,
+            current_guess: 0,
             is_first_guess: true,
         }
     }
 
-    fn populate_set_with_solutions(is_empty_allowed: bool, width: usize) -> HashSet<Line> {
-        let mut set_of_all_combinations = HashSet::new();
+    fn populate_set_with_solutions(space: &CodeSpace) -> Vec<Code> {
+        space.codes()
+    }
 
-        let color_limit = if is_empty_allowed { 7 } else { 6 };
+    fn make_first_guess(&mut self) -> Code {
+        let mut rng = rng();
+        // Without repeats a two-color opening isn't allowed, and in a word
+        // game it is rarely a word; any code is as good as another before
+        // the first feedback.
+        if self.space.no_repeats || self.space.dictionary.is_some() {
+            return *self
+                .possible_solutions
+                .choose(&mut rng)
+                .expect("possible_solutions set is empty");
+        }
+        let n = self.space.palette.len();
 
-        let valid_colors: Vec<Color> = (0..color_limit).map(|n| num_to_color(n)).collect();
+        let index1 = rng.random_range(0..n);
+        let mut index2 = rng.random_range(0..n);
 
-        // Buffer to hold the current line being built.
-        let mut current_buffer = Vec::with_capacity(width);
+        while index2 == index1 {
+            index2 = rng.random_range(0..n);
+        }
 
-        // Start recursion.
-        Self::generate_recursive(
-            width,
-            &valid_colors,
-            &mut current_buffer,
-            &mut set_of_all_combinations,
-        );
+        let middle_index = self.space.width / 2;
+        let mut code: Code = 0;
+        for i in 0..self.space.width {
+            let index = if i < middle_index { index1 } else { index2 };
+            code = code * self.space.base() + index as Code;
+        }
+        code
+    }
 
-        set_of_all_combinations
+    fn answer(&self, target: Code, guess: Code) -> usize {
+        match &self.feedback_table {
+            Some(table) => table.get_encoded(target, guess),
+            None => answer_between(&self.space, target, guess),
+        }
     }
 
-    fn generate_recursive(
-        target_width: usize,
-        valid_colors: &Vec<Color>,
-        current_buffer: &mut Vec<Color>,
-        results: &mut HashSet<Line>,
-    ) {
-        // BASE CASE: If buffer is full we have a complete line.
-        if current_buffer.len() == target_width {
-            results.insert(Line::new(current_buffer.clone()));
+    fn prune_non_viable_solutions(&mut self) {
+        if self.is_first_guess {
             return;
         }
-        // RECURSIVE STEP: Try every color for the current slot.
-        for color in valid_colors {
-            current_buffer.push(*color); // Choose
+        if self.space.max_lies > 0 {
+            self.count_lies();
+            return;
+        }
+        // Only retain codes that give the same answer as self.current_answer
+        // retain calls the closure for all elements
+        // If closure returns false, remove, else keep solution.
+        let mut possible_solutions = std::mem::take(&mut self.possible_solutions);
+        possible_solutions.retain(|code| {
+            // |code| = for each code in possible_solutions...
+            // Compare answer to self.current_answer.
+            let simulated_answer = self.answer(*code, self.current_guess);
+            // If match, retain else remove.
+            simulated_answer == self.current_answer
+        });
+        self.lie_counts.truncate(possible_solutions.len());
+        self.possible_solutions = possible_solutions;
+    }
 
-            Self::generate_recursive(target_width, valid_colors, current_buffer, results);
+    // With lies allowed, an answer that doesn't match a code counts as one
+    // more lie against it, and only codes with at most max_lies are kept.
+    // A win is never faked or hidden, so the guess itself goes on any other
+    // answer and every other code on a win.
+    fn count_lies(&mut self) {
+        let win = self.space.win_answer();
+        let possible_solutions = std::mem::take(&mut self.possible_solutions);
+        let lie_counts = std::mem::take(&mut self.lie_counts);
+        for (code, lies) in possible_solutions.into_iter().zip(lie_counts) {
+            let simulated_answer = self.answer(code, self.current_guess);
+            if (simulated_answer == win) != (self.current_answer == win) {
+                continue;
+            }
+            let lies = lies + (simulated_answer != self.current_answer) as u8;
+            if lies <= self.space.max_lies {
+                self.possible_solutions.push(code);
+                self.lie_counts.push(lies);
+            }
+        }
+    }
 
-            current_buffer.pop();
+    // Keeps only the solutions `keep` accepts, with their lie counts.
+    pub fn retain_solutions(&mut self, mut keep: impl FnMut(Code) -> bool) {
+        let possible_solutions = std::mem::take(&mut self.possible_solutions);
+        let lie_counts = std::mem::take(&mut self.lie_counts);
+        for (code, lies) in possible_solutions.into_iter().zip(lie_counts) {
+            if keep(code) {
+                self.possible_solutions.push(code);
+                self.lie_counts.push(lies);
+            }
         }
     }
 
-    fn make_first_guess(&mut self) -> Line {
-        let mut rng = rng();
-        let n = self.available_colors.len();
+    pub fn reset_for_new_round(&mut self) {
+        self.possible_solutions = Self::populate_set_with_solutions(&self.space);
+        self.lie_counts = vec![0; self.possible_solutions.len()];
+        self.guessed_codes.clear();
+        self.symmetries = Symmetries::new(&self.space);
+        self.current_answer = 0;
+        self.is_first_guess = true;
+    }
 
-        let index1 = rng.random_range(0..n);
-        let mut index2 = rng.random_range(0..n);
+    pub fn next_guess(&mut self) -> Line {
+        self.next_guess_within(&SearchControl::unlimited())
+    }
 
-        while index2 == index1 {
-            index2 = rng.random_range(0..n);
+    pub fn next_guess_within(&mut self, control: &SearchControl) -> Line {
+        let code = self.make_educated_guess_parallel(control);
+        self.space.unrank(code)
+    }
+
+    pub fn observe_feedback(&mut self, guess: &Line, feedback: Feedback) {
+        if self.space.feedback_rule == FeedbackRule::Positional {
+            // Only the counts are known, so keep the codes that give them.
+            self.is_first_guess = false;
+            let code = self.space.rank(guess);
+            let counting = self
+                .space
+                .clone()
+                .with_feedback_rule(FeedbackRule::Standard);
+            let answer = feedback.encode(counting.width);
+            self.retain_solutions(|solution| answer_between(&counting, solution, code) == answer);
+            self.symmetries = self.symmetries.after_guess(code);
+            return;
         }
+        self.observe_answer(guess, feedback.encode(self.space.width));
+    }
 
-        let color1 = self.available_colors[index1];
-        let color2 = self.available_colors[index2];
+    // The answer as the flag pegs on the board show it, per-position marks
+    // included.
+    pub fn observe_flags(&mut self, guess: &Line, flags: &Line) {
+        self.observe_answer(guess, self.space.answer_of_flags(flags));
+    }
 
-        let middle_index = self.no_of_pegs / 2;
-        let mut colors = Vec::new();
-        for i in 0..self.no_of_pegs {
-            if i < middle_index {
-                colors.push(color1);
-            } else {
-                colors.push(color2);
+    // An answer packed by CodeSpace::answer, as largest_partition gives it.
+    // Under the positional rule only reorderings of positions with the same
+    // mark stay symmetries.
+    pub fn observe_answer(&mut self, guess: &Line, answer: usize) {
+        // A bot replaying someone else's board never made an opening itself.
+        self.is_first_guess = false;
+        self.current_guess = self.space.rank(guess);
+        self.current_answer = answer;
+        self.symmetries = self.symmetries.after_guess(self.current_guess);
+        if self.space.feedback_rule == FeedbackRule::Positional {
+            let marks = flags_from_marks(answer, self.space.width);
+            let marks: Vec<u8> = marks
+                .pegs
+                .iter()
+                .map(|peg| peg.color.symbol() as u8)
+                .collect();
+            self.symmetries = self.symmetries.after_marks(&marks);
+        }
+        self.prune_non_viable_solutions();
+    }
+
+    // Number of remaining solutions that would answer `guess` with each answer,
+    // indexed by CodeSpace::answer. With lies allowed, also the number still
+    // in play after each answer (None if no solution has a lie left): a
+    // solution with a lie left survives every other answer some code gives
+    // the guess, except a win.
+    fn partition_counts(&self, guess: Code) -> (Vec<usize>, Option<Vec<usize>>) {
+        let win = self.space.win_answer();
+        let mut answer_counts = vec![0; self.space.answer_count()];
+        let can_lie = self.space.max_lies > 0;
+        let mut liar_counts = if can_lie {
+            vec![0; answer_counts.len()]
+        } else {
+            Vec::new()
+        };
+        let mut liars = 0;
+        for (solution, lies) in self.possible_solutions.iter().zip(&self.lie_counts) {
+            let answer = self.answer(*solution, guess);
+            answer_counts[answer] += 1;
+            if can_lie && answer != win && *lies < self.space.max_lies {
+                liar_counts[answer] += 1;
+                liars += 1;
             }
         }
-        Line::new(colors)
+        if liars == 0 {
+            return (answer_counts, None);
+        }
+        let possible_answers = self.possible_answers(guess);
+        let survivor_counts = answer_counts
+            .iter()
+            .zip(liar_counts)
+            .enumerate()
+            .map(|(answer, (count, liar_count))| {
+                if answer != win && possible_answers[answer] {
+                    count + liars - liar_count
+                } else {
+                    *count
+                }
+            })
+            .collect();
+        (answer_counts, Some(survivor_counts))
+    }
+
+    // Whether some code in the space gives `guess` each answer, so a lie
+    // can't be one no board could show. Relabeling the colors keeps the
+    // answers a guess can get, so they are worked out once per pattern of
+    // repeated colors; not so for words, which are keyed by their letters.
+    fn possible_answers(&self, guess: Code) -> Arc<Vec<bool>> {
+        let mut pattern = vec![0; self.space.width];
+        self.space.digits(guess, &mut pattern);
+        let mut colors = Vec::new();
+        let relabeled = match self.space.dictionary {
+            Some(_) => &mut [][..],
+            None => &mut pattern[..],
+        };
+        for digit in relabeled.iter_mut() {
+            *digit = match colors.iter().position(|color| color == digit) {
+                Some(label) => label as u8,
+                None => {
+                    colors.push(*digit);
+                    (colors.len() - 1) as u8
+                }
+            };
+        }
+        let known = || {
+            self.possible_answers
+                .lock()
+                .expect("possible answers lock poisoned")
+        };
+        if let Some(answers) = known().get(&pattern) {
+            return Arc::clone(answers);
+        }
+        // Worked out without the lock, so the other scoring threads aren't
+        // held up behind a scan of the whole space.
+        let mut answers = vec![false; self.space.answer_count()];
+        let mut digits = vec![0; self.space.width];
+        for code in self.space.codes() {
+            self.space.digits(code, &mut digits);
+            answers[self.space.answer(&digits, &pattern)] = true;
+        }
+        let answers = Arc::new(answers);
+        Arc::clone(known().entry(pattern).or_insert(answers))
     }
 
-    fn prune_non_viable_solutions(&mut self) {
-        if !self.is_first_guess {
-            // Only retain lines that give the same feedback as self.current_feedback
-            // retain calls the closure for all elements
-            // If closure returns false, remove, else keep solution.
-            self.possible_solutions.retain(|line| {
-                // |line| = for each line in possible_solutions...
-                // Compare feedback to self.current_feedback.
-                let (_, simulated_feedback) = check_for_matches(line, &self.current_guess);
-                // If match, retain else remove.
-                simulated_feedback == self.current_feedback
-            });
-        }
-    }
-
-    fn reset_possible_solutions(&mut self, gamestate: &Gamestate) {
-        self.possible_solutions =
-            Self::populate_set_with_solutions(gamestate.is_empty_allowed, gamestate.pegs_in_a_line);
-        self.guessed_lines.clear();
-        self.current_feedback = Feedback::empty();
-        self.is_first_guess = true;
+    // The answer to `guess` shared by the most remaining solutions; on a tie,
+    // any answer other than all-correct. Used by the adversarial Code Maker.
+    pub fn largest_partition(&self, guess: &Line) -> usize {
+        self.largest_partition_where(guess, |_| true)
     }
 
-    fn make_educated_guess_parallel(&mut self) -> Line {
-        // Just make a starting guess if is_first_guess
-        if self.is_first_guess {
+    // The same among the answers `allowed` accepts, for a Maker that may
+    // only give some of them.
+    pub fn largest_partition_where(&self, guess: &Line, allowed: impl Fn(usize) -> bool) -> usize {
+        let win = self.space.win_answer();
+        let (counts, survivor_counts) = self.partition_counts(self.space.rank(guess));
+        let counts = survivor_counts.unwrap_or(counts);
+        let (answer, _) = counts
+            .iter()
+            .enumerate()
+            .filter(|(answer, _)| allowed(*answer))
+            .max_by_key(|(answer, count)| (**count, *answer != win))
+            .expect("answer partition is empty");
+        answer
+    }
+
+    // Sort key for a guess: lowest heuristic cost, then consistent candidates
+    // before ruled-out codes, then lowest Code.
+    fn guess_key(&self, guess: Code) -> (u64, bool, Code) {
+        let heuristic = self.settings.heuristic;
+        let cost = match self.partition_counts(guess) {
+            (counts, Some(survivor_counts)) => heuristic.cost_with_lies(&counts, &survivor_counts),
+            (counts, None) => heuristic.cost(&counts),
+        };
+        let is_ruled_out = self.possible_solutions.binary_search(&guess).is_err();
+        (cost, is_ruled_out, guess)
+    }
+
+    // A random consistent candidate, or for Greedy the best split among a few.
+    fn make_casual_guess(&self) -> Code {
+        let mut rng = rng();
+        let sample_size = match self.settings.difficulty {
+            Difficulty::Greedy => GREEDY_CANDIDATES,
+            _ => 1,
+        };
+        self.possible_solutions
+            .choose_multiple(&mut rng, sample_size)
+            .map(|guess| self.guess_key(*guess))
+            .min()
+            .map(|(_, _, guess)| guess)
+            .expect("possible_solutions set is empty")
+    }
+
+    // Scores candidate guesses in parallel and plays the best. If `control`
+    // says stop, candidates not scored yet are skipped and the best of those
+    // already scored is played (the lowest candidate if none was).
+    fn make_educated_guess_parallel(&mut self, control: &SearchControl) -> Code {
+        if self.settings.difficulty != Difficulty::Full {
             self.is_first_guess = false;
-            let guess = self.make_first_guess();
-            self.guessed_lines.push(guess.clone());
+            let guess = self.make_casual_guess();
+            self.guessed_codes.push(guess);
             return guess;
         }
 
-        // Parallel iterator over possible guesses
-        let best_guess = self
-            .possible_solutions
-            .par_iter() // <-- parallel
-            .filter(|guess| !self.guessed_lines.contains(guess))
-            .map(|guess| {
-                // Map to count how often each feedback occurs when comparing this guess with all
-                // potential solutions.
-                let mut feedback_counts: FxHashMap<Feedback, usize> = FxHashMap::default();
-
-                // Increment the usize variable in solutions that would produce this feedback
-                // - entry(feedback) looks up the key 'feedback' in the Map
-                // - or_insert(0) inserts 0 if the key doesn't exist yet
-                // -*... += 1 derefences the mutable reference returned by or_insert
-                for solution in &self.possible_solutions {
-                    let (_, feedback) = check_for_matches(solution, guess);
-                    *feedback_counts.entry(feedback).or_insert(0) += 1;
-                }
-                // Find the feedback value that would leave the most remaining solutions (worst-case)
-                // - values() returns references to the usize counts
-                // - cloned() converts them to owned usize values
-                // - max() finds the largest count
-                // unwrap_or(0) handles the case where the map is empty
-                let worst_case = feedback_counts.values().cloned().max().unwrap_or(0);
-                (guess.clone(), worst_case)
-            })
-            .min_by_key(|(_, worst_case)| *worst_case) // minimize worst-case
-            .map(|(guess, _)| guess.clone())
-            .or_else(|| self.possible_solutions.iter().next().cloned())
+        // Just make a starting guess if is_first_guess (Knuth and Optimal compute their opening)
+        if self.is_first_guess {
+            self.is_first_guess = false;
+            if self.settings.mode == BotMode::Classic {
+                let guess = self.make_first_guess();
+                self.guessed_codes.push(guess);
+                return guess;
+            }
+        }
+
+        // Cut short before any guess was fully searched, the Optimal solver
+        // gives way to the Knuth search below.
+        if let Some(solver) = &self.optimal_solver
+            && let Some(best_guess) = solver
+                .lock()
+                .expect("optimal solver lock poisoned")
+                .best_guess_within(&self.possible_solutions, &self.symmetries, control)
+        {
+            self.guessed_codes.push(best_guess);
+            return best_guess;
+        }
+
+        // Parallel iterator over possible guesses. Codes that are symmetric images
+        // of each other score the same, so only the lowest of each class is scored.
+        // Progress counts every code looked at, scored or skipped.
+        let best_key = match self.settings.mode {
+            BotMode::Classic | BotMode::Sampling | BotMode::Constraint => {
+                control.set_total(self.possible_solutions.len() as u64);
+                self.possible_solutions
+                    .par_iter() // <-- parallel
+                    .filter(|_| !control.should_stop())
+                    .inspect(|_| control.advance(1))
+                    .filter(|guess| !self.guessed_codes.contains(guess))
+                    .filter(|guess| self.symmetries.is_canonical(**guess))
+                    .map(|guess| self.guess_key(*guess))
+                    .min()
+            }
+            BotMode::Knuth | BotMode::Optimal => {
+                let codes = self.space.codes();
+                control.set_total(codes.len() as u64);
+                codes
+                    .into_par_iter()
+                    .filter(|_| !control.should_stop())
+                    .inspect(|_| control.advance(1))
+                    .filter(|guess| self.symmetries.is_canonical(*guess))
+                    .map(|guess| self.guess_key(guess))
+                    .min()
+            }
+        };
+        let best_guess = best_key
+            .map(|(_, _, guess)| guess)
+            .or_else(|| self.possible_solutions.first().copied())
             .expect("possible_solutions set is empty");
 
-        self.guessed_lines.push(best_guess.clone());
+        self.guessed_codes.push(best_guess);
         best_guess
     }
 }
 
-pub fn reset_bot_for_new_round(bot: &mut Option<Bot>, gamestate: &Gamestate) {
-    if let Some(b) = bot.as_mut() {
-        b.reset_possible_solutions(gamestate);
+impl Bot {
+    // Static Mastermind: the batch, then the final guess once the engine has
+    // fed back every answer in the batch. Below Full the batch is one guess.
+    pub fn next_batch_within(&mut self, max_guesses: usize, control: &SearchControl) -> Vec<Line> {
+        // make_educated_guess_parallel records its own guess.
+        let batch = if self.settings.difficulty == Difficulty::Full {
+            let batch = self.make_separating_batch(max_guesses, control);
+            self.guessed_codes.extend(&batch);
+            batch
+        } else {
+            vec![self.make_educated_guess_parallel(control)]
+        };
+        self.is_first_guess = false;
+        batch.iter().map(|code| self.space.unrank(*code)).collect()
+    }
+
+    // Any code that fits the batch's answers; a separating batch leaves one.
+    pub fn final_guess(&mut self) -> Line {
+        let code = *self
+            .possible_solutions
+            .choose(&mut rng())
+            .expect("possible_solutions set is empty");
+        self.guessed_codes.push(code);
+        self.space.unrank(code)
+    }
+
+    // Greedily adds the guess that best splits the parts the remaining
+    // solutions fall into by their feedback to every guess so far (ranked by
+    // the heuristic, then by the number of parts, which breaks the many ties
+    // late in the batch), until each part is a single code and the final guess
+    // is sure to win, or the batch is full.
+    fn make_separating_batch(&self, max_guesses: usize, control: &SearchControl) -> Vec<Code> {
+        let guesses = match self.settings.mode {
+            BotMode::Knuth | BotMode::Optimal => self.space.codes(),
+            _ => self.possible_solutions.clone(),
+        };
+        let mut parts = vec![0; self.possible_solutions.len()];
+        let mut part_count = 1;
+        let mut symmetries = self.symmetries.clone();
+        let mut batch = Vec::new();
+        control.set_total((max_guesses * guesses.len()) as u64);
+
+        while batch.len() < max_guesses && part_count < parts.len() {
+            let best = guesses
+                .par_iter()
+                .filter(|_| !control.should_stop())
+                .inspect(|_| control.advance(1))
+                .filter(|guess| !batch.contains(*guess))
+                .filter(|guess| symmetries.is_canonical(**guess))
+                .map(|guess| {
+                    let sizes = part_sizes(&self.refined_parts(&parts, *guess), parts.len());
+                    let part_count = sizes.iter().filter(|size| **size > 0).count();
+                    (
+                        self.settings.heuristic.cost(&sizes),
+                        Reverse(part_count),
+                        *guess,
+                    )
+                })
+                .min();
+            let Some((_, _, guess)) = best else {
+                break;
+            };
+            let refined = self.refined_parts(&parts, guess);
+            let mut ids = refined.clone();
+            ids.sort_unstable();
+            ids.dedup();
+            parts = refined
+                .iter()
+                .map(|key| ids.binary_search(key).expect("key is listed") as u64)
+                .collect();
+            part_count = ids.len();
+            symmetries = symmetries.after_guess(guess);
+            batch.push(guess);
+        }
+        if batch.is_empty() {
+            batch.push(
+                *self
+                    .possible_solutions
+                    .first()
+                    .expect("possible_solutions set is empty"),
+            );
+        }
+        batch
+    }
+
+    // The part of each remaining solution once `guess` is added: its old part
+    // and its answer to the guess, packed into one key.
+    fn refined_parts(&self, parts: &[u64], guess: Code) -> Vec<u64> {
+        let answers = self.space.answer_count() as u64;
+        self.possible_solutions
+            .iter()
+            .zip(parts)
+            .map(|(solution, part)| part * answers + self.answer(*solution, guess) as u64)
+            .collect()
+    }
+}
+
+impl BreakerStrategy for Bot {
+    fn next_guess(&mut self) -> Line {
+        Bot::next_guess(self)
+    }
+
+    fn next_guess_within(&mut self, control: &SearchControl) -> Line {
+        Bot::next_guess_within(self, control)
+    }
+
+    fn observe_feedback(&mut self, guess: &Line, feedback: Feedback) {
+        Bot::observe_feedback(self, guess, feedback)
+    }
+
+    fn observe_flags(&mut self, guess: &Line, flags: &Line) {
+        Bot::observe_flags(self, guess, flags)
+    }
+
+    fn reset_for_round(&mut self) {
+        self.reset_for_new_round()
+    }
+
+    fn next_batch_within(&mut self, max_guesses: usize, control: &SearchControl) -> Vec<Line> {
+        Bot::next_batch_within(self, max_guesses, control)
+    }
+
+    // Picking among the codes left takes no search.
+    fn final_guess_within(&mut self, _control: &SearchControl) -> Line {
+        Bot::final_guess(self)
     }
 }
 
-pub fn handle_bot_input(bot_ref: &mut Bot, gamestate: &mut Gamestate) {
-    let new_guess = bot_ref.make_educated_guess_parallel();
-    let (flags, feedback) = check_for_matches(&gamestate.target_line, &new_guess);
-    bot_ref.current_guess = new_guess.clone();
-    bot_ref.current_feedback = feedback;
-    gamestate.guessed_lines.push(new_guess.clone());
-    gamestate.flag_pegs.push(flags);
-    bot_ref.prune_non_viable_solutions();
+pub struct RandomBot {
+    space: CodeSpace,
 }
 
-pub fn bot_guess(gamestate: &mut Gamestate, bot: &mut Option<Bot>) {
-    if let Some(bot_ref) = bot.as_mut() {
-        handle_bot_input(bot_ref, gamestate);
+impl RandomBot {
+    pub fn new(palette: Vec<Color>, no_of_pegs: usize) -> Self {
+        Self::with_space(CodeSpace::new(palette, no_of_pegs))
+    }
+
+    pub fn with_space(space: CodeSpace) -> Self {
+        RandomBot { space }
     }
 }
 
-fn num_to_color(n: u8) -> Color {
-    match n {
-        0 => Color::White,
-        1 => Color::Black,
-        2 => Color::Red,
-        3 => Color::Green,
-        4 => Color::Blue,
-        5 => Color::Yellow,
-        _ => Color::Empty,
+impl BreakerStrategy for RandomBot {
+    // Word games pick among the words; the colors of any other game are
+    // drawn peg by peg, so the space is never enumerated.
+    fn next_guess(&mut self) -> Line {
+        let mut rng = rng();
+        let CodeSpace { palette, width, .. } = &self.space;
+        if self.space.dictionary.is_some() {
+            let code = *self
+                .space
+                .codes()
+                .choose(&mut rng)
+                .expect("the word list is empty");
+            return self.space.unrank(code);
+        }
+        if self.space.no_repeats {
+            return Line::new(palette.choose_multiple(&mut rng, *width).copied().collect());
+        }
+        Line::new(
+            (0..*width)
+                .map(|_| *palette.choose(&mut rng).expect("palette is empty"))
+                .collect(),
+        )
     }
+
+    fn observe_feedback(&mut self, _guess: &Line, _feedback: Feedback) {}
+
+    fn reset_for_round(&mut self) {}
+}
+
+// Number of solutions per part, padded with empty parts to `len` so
+// MostParts compares every guess over the same number of parts.
+fn part_sizes(keys: &[u64], len: usize) -> Vec<usize> {
+    let mut keys = keys.to_vec();
+    keys.sort_unstable();
+    let mut sizes: Vec<usize> = keys
+        .chunk_by(|a, b| a == b)
+        .map(|part| part.len())
+        .collect();
+    sizes.resize(len, 0);
+    sizes
+}
+
+fn answer_between(space: &CodeSpace, target: Code, guess: Code) -> usize {
+    let width = space.width;
+    let mut target_digits = [0; MAX_CODE_WIDTH];
+    let mut guess_digits = [0; MAX_CODE_WIDTH];
+    space.digits(target, &mut target_digits[..width]);
+    space.digits(guess, &mut guess_digits[..width]);
+
+    space.answer(&target_digits[..width], &guess_digits[..width])
 }
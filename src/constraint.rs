@@ -0,0 +1,437 @@
+/*
+    Constraint solver module.
+
+    Provides ConstraintBot, a Code Breaker for wide codes (roughly 10-30 pegs)
+    that keeps every clue as a constraint and finds its next guess, a code
+    consistent with all clues so far, by backtracking search with propagation.
+    Nothing is enumerated up front, so memory stays proportional to the clues.
+    Finding a consistent code is NP-hard in general: guesses come in well under
+    a second up to about 16 pegs with 8 colors, but past about 20 pegs a single
+    guess can take seconds to minutes.
+
+    Public API:
+    - MAX_CONSTRAINT_WIDTH: the widest code the solver handles (positions are bitsets).
+    - ConstraintBot::new: creates a bot for a palette, peg count and BotSettings.
+    - impl BreakerStrategy for ConstraintBot.
+
+    Internal helpers (private):
+    - Solver: the search state for one guess.
+    - Solver::choose_counts: phase 1, how many pegs of each color the code has.
+      Each clue fixes sum(min(count, count in guess)) over its colors.
+    - Solver::assign: phase 2, which color goes where, given the counts. Each
+      clue fixes how many positions match its guess exactly.
+    - Solver::propagate: the positions each color can still take in phase 2.
+
+    Notes:
+    - Per-position exclusions come straight from the clues: no exact matches
+      rule out a guess's colors at their positions, no matches at all rule
+      its colors out entirely.
+    - Phase 2 fills the position with the fewest possible colors first and
+      abandons a branch as soon as some clue can no longer get exactly its
+      exact matches.
+    - The search restarts with a doubled node budget when it gets stuck, since
+      one unlucky early choice can otherwise trap it in a huge dead subtree.
+    - Values are tried in random order, so the bot plays a random consistent
+      code rather than always the lowest one.
+    - If the clues contradict each other (e.g. a mistaken human answer) there
+      is no consistent code; the bot then plays a random code. It does the same
+      when the time limit passes or the player cancels before a code is found.
+*/
+
+use crate::{
+    bot::BotSettings,
+    gamelogic::MAX_SYMBOLS,
+    sampling::{Clue, to_digits, to_line},
+    strategy::{BreakerStrategy, SearchControl},
+    types::{Color, Feedback, Line},
+};
+
+use rand::{Rng, rng, rngs::ThreadRng, seq::SliceRandom};
+
+pub const MAX_CONSTRAINT_WIDTH: usize = 64;
+
+const UNASSIGNED: u8 = u8::MAX;
+const FIRST_NODE_LIMIT: u64 = 1_000;
+const NODES_PER_STOP_CHECK: u64 = 256;
+
+// A set of positions, bit i for position i.
+type Positions = u64;
+
+pub struct ConstraintBot {
+    pub settings: BotSettings,
+    palette: Vec<Color>,
+    width: usize,
+    clues: Vec<Clue>,
+}
+
+impl ConstraintBot {
+    pub fn new(palette: Vec<Color>, no_of_pegs: usize, settings: BotSettings) -> Self {
+        assert!(
+            no_of_pegs <= MAX_CONSTRAINT_WIDTH,
+            "too many pegs for the constraint solver"
+        );
+        ConstraintBot {
+            settings,
+            palette,
+            width: no_of_pegs,
+            clues: Vec::new(),
+        }
+    }
+
+    fn random_code(&self) -> Vec<u8> {
+        let mut rng = rng();
+        (0..self.width)
+            .map(|_| rng.random_range(0..self.palette.len()) as u8)
+            .collect()
+    }
+}
+
+impl BreakerStrategy for ConstraintBot {
+    fn next_guess(&mut self) -> Line {
+        self.next_guess_within(&SearchControl::unlimited())
+    }
+
+    fn next_guess_within(&mut self, control: &SearchControl) -> Line {
+        let code = Solver::new(&self.clues, self.width, self.palette.len(), control)
+            .solve()
+            .unwrap_or_else(|| self.random_code());
+        to_line(&self.palette, &code)
+    }
+
+    fn observe_feedback(&mut self, guess: &Line, feedback: Feedback) {
+        let guess = to_digits(&self.palette, guess);
+        self.clues
+            .push(Clue::new(guess, feedback, self.palette.len()));
+    }
+
+    fn reset_for_round(&mut self) {
+        self.clues.clear();
+    }
+}
+
+// What the colors counted so far in phase 1 contribute to one clue.
+#[derive(Clone, Copy, Default)]
+struct Tally {
+    total: u16,
+    exact_min: u16,
+    exact_max: u16,
+}
+
+struct Solver<'a> {
+    clues: &'a [Clue],
+    width: usize,
+    base: usize,
+    // Positions each color may take, from the per-position exclusions.
+    allowed: [Positions; MAX_SYMBOLS],
+    // Per clue, the positions where each color matches its guess exactly.
+    spots: Vec<[Positions; MAX_SYMBOLS]>,
+    // Phase 2 state: pegs of each color still to place, open positions, the
+    // partial code and the exact matches each clue already has.
+    remaining: [u16; MAX_SYMBOLS],
+    open: Positions,
+    code: Vec<u8>,
+    exact_matches: Vec<u16>,
+    // Nodes visited in the current attempt, and the budget for it.
+    nodes: u64,
+    node_limit: u64,
+    control: &'a SearchControl,
+    is_stopped: bool,
+    rng: ThreadRng,
+}
+
+impl<'a> Solver<'a> {
+    fn new(clues: &'a [Clue], width: usize, base: usize, control: &'a SearchControl) -> Self {
+        let all = if width == MAX_CONSTRAINT_WIDTH {
+            Positions::MAX
+        } else {
+            (1 << width) - 1
+        };
+        let mut allowed = [0; MAX_SYMBOLS];
+        allowed[..base].fill(all);
+        let mut spots = Vec::with_capacity(clues.len());
+        for clue in clues {
+            let mut clue_spots = [0; MAX_SYMBOLS];
+            for (position, color) in clue.guess.iter().enumerate() {
+                clue_spots[*color as usize] |= 1 << position;
+                if clue.total_matches == 0 {
+                    allowed[*color as usize] = 0;
+                } else if clue.correct_position == 0 {
+                    allowed[*color as usize] &= !(1 << position);
+                }
+            }
+            spots.push(clue_spots);
+        }
+
+        Solver {
+            clues,
+            width,
+            base,
+            allowed,
+            spots,
+            remaining: [0; MAX_SYMBOLS],
+            open: all,
+            code: vec![UNASSIGNED; width],
+            exact_matches: vec![0; clues.len()],
+            nodes: 0,
+            node_limit: FIRST_NODE_LIMIT,
+            control,
+            is_stopped: false,
+            rng: rng(),
+        }
+    }
+
+    // An attempt that finishes within its budget without a code proves there
+    // is none; one that runs out is retried with twice the budget. Gives up
+    // when the control says stop. Progress counts nodes, with no known total.
+    fn solve(mut self) -> Option<Vec<u8>> {
+        self.control.set_total(0);
+        loop {
+            self.nodes = 0;
+            let mut colors: Vec<usize> = (0..self.base).collect();
+            colors.shuffle(&mut self.rng);
+            let mut tallies = vec![Tally::default(); self.clues.len()];
+            if self.choose_counts(&colors, &mut tallies, self.width as u16) {
+                return Some(self.code);
+            }
+            if self.is_stopped || self.nodes <= self.node_limit {
+                return None;
+            }
+            self.node_limit *= 2;
+        }
+    }
+
+    fn is_out_of_budget(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(NODES_PER_STOP_CHECK) {
+            self.control.advance(NODES_PER_STOP_CHECK);
+            self.is_stopped = self.control.should_stop();
+        }
+        self.is_stopped || self.nodes > self.node_limit
+    }
+
+    // Allowed positions of `color` that match clue `j` exactly, and the others.
+    fn split(&self, j: usize, color: usize) -> (u16, u16) {
+        let matching = self.allowed[color] & self.spots[j][color];
+        let other = self.allowed[color] & !self.spots[j][color];
+        (matching.count_ones() as u16, other.count_ones() as u16)
+    }
+
+    // `tally` after counting `count` pegs of `color` for clue `j`. A color adds
+    // at most its count capped by its matching spots to the exact matches,
+    // and at least the pegs that don't fit in its other spots.
+    fn tally_with(&self, j: usize, tally: Tally, color: usize, count: u16) -> Tally {
+        let (matching, other) = self.split(j, color);
+        Tally {
+            total: tally.total + count.min(self.clues[j].guess_counts[color]),
+            exact_min: tally.exact_min + count.saturating_sub(other),
+            exact_max: tally.exact_max + count.min(matching),
+        }
+    }
+
+    // Phase 1. Picks how many pegs of colors[0] the code has and recurses on
+    // the rest; once every color has a count, runs phase 2. `tallies` holds,
+    // per clue, what the colors counted so far contribute. A count is only
+    // tried if every clue can still end up with exactly its total matches and
+    // its exact matches are still within reach.
+    fn choose_counts(&mut self, colors: &[usize], tallies: &mut [Tally], pegs_left: u16) -> bool {
+        if self.is_out_of_budget() {
+            return false;
+        }
+        let Some((&color, rest)) = colors.split_first() else {
+            return pegs_left == 0 && self.assign(0);
+        };
+        let max_count = (self.allowed[color].count_ones() as u16).min(pegs_left);
+        let mut counts: Vec<u16> = if rest.is_empty() {
+            // The last color takes whatever is left.
+            if pegs_left > max_count {
+                return false;
+            }
+            vec![pegs_left]
+        } else {
+            (0..=max_count).collect()
+        };
+        counts.shuffle(&mut self.rng);
+
+        let room_after: u16 = rest
+            .iter()
+            .map(|c| self.allowed[*c].count_ones() as u16)
+            .sum();
+        for count in counts {
+            let pegs_after = pegs_left - count;
+            if pegs_after > room_after {
+                continue;
+            }
+            let is_feasible = (0..self.clues.len()).all(|j| {
+                let clue = &self.clues[j];
+                let tally = self.tally_with(j, tallies[j], color, count);
+                let total_after: u16 = rest.iter().map(|c| clue.guess_counts[*c]).sum();
+                let exact_after: u16 = rest.iter().map(|c| self.split(j, *c).0).sum();
+                tally.total <= clue.total_matches
+                    && tally.total + total_after.min(pegs_after) >= clue.total_matches
+                    && tally.exact_min <= clue.correct_position
+                    && tally.exact_max + exact_after.min(pegs_after) >= clue.correct_position
+            });
+            if !is_feasible {
+                continue;
+            }
+
+            let saved = tallies.to_vec();
+            for (j, tally) in tallies.iter_mut().enumerate() {
+                *tally = self.tally_with(j, *tally, color, count);
+            }
+            self.remaining[color] = count;
+            if self.choose_counts(rest, tallies, pegs_after) {
+                return true;
+            }
+            tallies.copy_from_slice(&saved);
+        }
+        self.remaining[color] = 0;
+        false
+    }
+
+    // Phase 2 propagation: the open positions each color can still take, or
+    // None if some clue can no longer get exactly its exact matches. For each
+    // clue and color, the pegs left land on spots where they match the clue's
+    // guess or on other spots; that bounds the exact matches still to come
+    // from below (pegs that don't fit in the other spots) and above (pegs that
+    // fit in the matching spots). Until nothing changes:
+    // - a color needs as many open positions as it has pegs left;
+    // - a clue whose missing exact matches fall outside the bounds fails;
+    // - a clue at its upper bound keeps every color on its matching spots,
+    //   filling them all if it has the pegs to;
+    // - a clue at its lower bound keeps every color off its matching spots,
+    //   filling all of its other spots if it has the pegs to;
+    // - every open position needs some color.
+    fn propagate(&self) -> Option<[Positions; MAX_SYMBOLS]> {
+        let base = self.base;
+        let mut places = [0; MAX_SYMBOLS];
+        for (c, positions) in places[..base].iter_mut().enumerate() {
+            if self.remaining[c] > 0 {
+                *positions = self.allowed[c] & self.open;
+            }
+        }
+
+        loop {
+            let before = places;
+            for (j, clue) in self.clues.iter().enumerate() {
+                let spots = &self.spots[j];
+                let mut at_least = 0;
+                let mut at_most = 0;
+                for c in 0..base {
+                    let matching = (places[c] & spots[c]).count_ones() as u16;
+                    let other = (places[c] & !spots[c]).count_ones() as u16;
+                    if matching + other < self.remaining[c] {
+                        return None;
+                    }
+                    at_least += self.remaining[c].saturating_sub(other);
+                    at_most += self.remaining[c].min(matching);
+                }
+                let needed = match clue.correct_position.checked_sub(self.exact_matches[j]) {
+                    Some(needed) if (at_least..=at_most).contains(&needed) => needed,
+                    _ => return None,
+                };
+                if needed != at_least && needed != at_most {
+                    continue;
+                }
+
+                for c in 0..base {
+                    let matching = places[c] & spots[c];
+                    let other = places[c] & !spots[c];
+                    let remaining = self.remaining[c] as u32;
+                    let mut filled = 0;
+                    if needed == at_most {
+                        if remaining >= matching.count_ones() {
+                            filled |= matching;
+                        }
+                        if remaining <= matching.count_ones() {
+                            places[c] &= spots[c];
+                        }
+                    }
+                    if needed == at_least {
+                        if remaining >= other.count_ones() {
+                            filled |= other;
+                        }
+                        if remaining <= other.count_ones() {
+                            places[c] &= !spots[c];
+                        }
+                    }
+                    if filled != 0 {
+                        for (d, positions) in places[..base].iter_mut().enumerate() {
+                            if d != c {
+                                *positions &= !filled;
+                            }
+                        }
+                    }
+                }
+            }
+
+            let covered = places[..base].iter().fold(0, |acc, p| acc | p);
+            if covered != self.open {
+                return None;
+            }
+            if places == before {
+                return Some(places);
+            }
+        }
+    }
+
+    // Phase 2. Fills the open position with the fewest possible colors first.
+    fn assign(&mut self, assigned: usize) -> bool {
+        if assigned == self.width {
+            return self
+                .clues
+                .iter()
+                .zip(&self.exact_matches)
+                .all(|(clue, exact)| *exact == clue.correct_position);
+        }
+        if self.is_out_of_budget() {
+            return false;
+        }
+        let Some(places) = self.propagate() else {
+            return false;
+        };
+
+        let colors_at = |position: usize| -> Vec<u8> {
+            (0..self.base as u8)
+                .filter(|c| places[*c as usize] & (1 << position) != 0)
+                .collect()
+        };
+        let position = (0..self.width)
+            .filter(|i| self.open & (1 << i) != 0)
+            .min_by_key(|i| colors_at(*i).len())
+            .expect("open positions remain");
+
+        let mut colors = colors_at(position);
+        colors.shuffle(&mut self.rng);
+        for color in colors {
+            self.place(position, color);
+            if self.assign(assigned + 1) {
+                return true;
+            }
+            self.unplace(position, color);
+        }
+        false
+    }
+
+    fn place(&mut self, position: usize, color: u8) {
+        for (spots, exact) in self.spots.iter().zip(self.exact_matches.iter_mut()) {
+            if spots[color as usize] & (1 << position) != 0 {
+                *exact += 1;
+            }
+        }
+        self.remaining[color as usize] -= 1;
+        self.open &= !(1 << position);
+        self.code[position] = color;
+    }
+
+    fn unplace(&mut self, position: usize, color: u8) {
+        for (spots, exact) in self.spots.iter().zip(self.exact_matches.iter_mut()) {
+            if spots[color as usize] & (1 << position) != 0 {
+                *exact -= 1;
+            }
+        }
+        self.remaining[color as usize] += 1;
+        self.open |= 1 << position;
+        self.code[position] = UNASSIGNED;
+    }
+}
@@ -9,19 +9,36 @@
     - draw_board: clears the screen and renders the full board given a Gamestate.
 
     Internal helpers (private):
+    - draw_marked_guesses / mark_symbol: the guesses and marks of the
+      positional rule.
+    - row_background / print_lies: how the answers that were lies are shown.
     - format_line: converts a Line into a spaced string of colored symbols.
-    - colored_symbol: maps a Color to its terminal-colored "●" representation.
+    - format_colors: same as format_line for a plain slice of colors (used by the legend).
+    - colored_symbol: maps a Color to its terminal-colored "●" representation,
+      or a word game's letter to the letter.
 
     Notes:
     - The output uses the `colored` crate for styling.
     - Spacing and padding are computed dynamically based on the number of pegs.
+    - A static round's batch appears all at once, with a separator before the
+      final guess.
+    - Under the black-pegs-only rule the flags column is headed "Exact" and
+      never holds white pegs.
+    - Under the positional rule the board has a single column: each guess
+      with its marks under it (green exact, yellow elsewhere in the code,
+      hollow absent), and a key to the marks above the guesses.
+    - With lies allowed, the answers that were lies are only shown once the
+      round is over: their rows turn red and a line under the board lists them.
+    - A word game's legend is the number of words in play.
     - No game logic is handled here; this module is purely presentation.
 */
 
-use crate::{
+use crate::parse::clear_screen;
+
+use mastermind::{
     gamestate::Gamestate,
-    parse::clear_screen,
-    types::{Color, Line},
+    lies::find_lies,
+    types::{Color, FeedbackRule, Line},
 };
 
 use colored::{ColoredString, Colorize};
@@ -38,17 +55,17 @@ pub fn draw_board(gamestate: &Gamestate) {
 
     // Define Legend
     // Note, The Colored version is for printing
-    let legend_colored = format!(
-        " Colors {} {} {} {} {} {} ",
-        symbol.white(),
-        symbol.black(),
-        symbol.red(),
-        symbol.blue(),
-        symbol.green(),
-        symbol.yellow()
-    );
-
-    let legend_plain = " Colors ● ● ● ● ● ● ";
+    let legend_colors = Color::palette(gamestate.number_of_colors, false);
+    let (legend_colored, legend_plain) = match &gamestate.dictionary {
+        Some(dictionary) => {
+            let legend = format!(" {} words ", dictionary.len());
+            (legend.clone(), legend)
+        }
+        None => (
+            format!(" Colors {} ", format_colors(&legend_colors)),
+            format!(" Colors {} ", vec![symbol; legend_colors.len()].join(" ")),
+        ),
+    };
     let visual_len = legend_plain.chars().count();
 
     // Wildcard Calculation
@@ -82,11 +99,28 @@ pub fn draw_board(gamestate: &Gamestate) {
         .on_bright_black()
     );
 
+    let lies = match gamestate.round_over {
+        true => find_lies(gamestate),
+        false => Vec::new(),
+    };
+
+    if gamestate.feedback_rule == FeedbackRule::Positional {
+        draw_marked_guesses(gamestate, total_width, &lies);
+        print_lies(gamestate, &lies);
+        return;
+    }
+
     println!("{}", top_separator.on_bright_black());
 
+    // Only exact matches are shown under the black-pegs-only rule
+    let hits_header = match gamestate.feedback_rule {
+        FeedbackRule::BlackOnly => "Exact",
+        _ => "Hits",
+    };
+
     println!(
         "{}",
-        format!("║{:^w$}║{:^w$}║", "Guesses", "Hits", w = col_width).on_bright_black()
+        format!("║{:^w$}║{:^w$}║", "Guesses", hits_header, w = col_width).on_bright_black()
     );
     println!("{}", separator.on_bright_black());
 
@@ -109,27 +143,108 @@ pub fn draw_board(gamestate: &Gamestate) {
         let guess_row = format_line(&gamestate.guessed_lines[i]);
         let flag_row = format_line(&gamestate.flag_pegs[i]);
 
-        println!(
-            "{}",
-            format!(
-                "║{}{}{}║{}{}{}║",
-                pad_l,
-                guess_row,
-                pad_r, // Column 1
-                pad_l,
-                flag_row,
-                pad_r // Column 2
-            )
-            .on_bright_black()
+        let row = format!(
+            "║{}{}{}║{}{}{}║",
+            pad_l,
+            guess_row,
+            pad_r, // Column 1
+            pad_l,
+            flag_row,
+            pad_r // Column 2
         );
+        println!("{}", row_background(row, lies.contains(&i)));
+        // A static round's batch is set apart from the final guess
+        if gamestate.static_mode && i + 1 == gamestate.batch_size {
+            println!("{}", separator.on_bright_black());
+        }
     }
     println!("{}", floor.on_bright_black());
+    print_lies(gamestate, &lies);
+}
+
+// The positional rule's board: one wide column, each guess with its marks
+// right under it, peg for peg.
+fn draw_marked_guesses(gamestate: &Gamestate, total_width: usize, lies: &[usize]) {
+    let separator = format!("╠{}╣", "═".repeat(total_width));
+    let floor = format!("╚{}╝", "═".repeat(total_width));
+    // Centers a row of colored text whose visible width is `visual_len`.
+    let centered = |content: &str, visual_len: usize, is_lie: bool| {
+        let padding = total_width.saturating_sub(visual_len);
+        let pad_l = " ".repeat(padding / 2);
+        let pad_r = " ".repeat(padding - padding / 2);
+        let row = format!("║{}{}{}║", pad_l, content, pad_r);
+        println!("{}", row_background(row, is_lie));
+    };
+
+    println!("{}", separator.on_bright_black());
+    let key = format!(
+        "{} exact {} near {} miss",
+        mark_symbol(Color::Black),
+        mark_symbol(Color::White),
+        mark_symbol(Color::Empty)
+    );
+    centered(&key, "● exact ● near ○ miss".chars().count(), false);
+    println!("{}", separator.on_bright_black());
+
+    let row_len = (gamestate.pegs_in_a_line * 2).saturating_sub(1);
+    for (i, (guess, flags)) in gamestate
+        .guessed_lines
+        .iter()
+        .zip(&gamestate.flag_pegs)
+        .enumerate()
+    {
+        centered(&format_line(guess), row_len, false);
+        let marks: Vec<String> = flags
+            .pegs
+            .iter()
+            .map(|peg| mark_symbol(peg.color).to_string())
+            .collect();
+        centered(&marks.join(" "), row_len, lies.contains(&i));
+        if gamestate.static_mode && i + 1 == gamestate.batch_size {
+            println!("{}", separator.on_bright_black());
+        }
+    }
+    println!("{}", floor.on_bright_black());
+}
+
+// A row of the board, red when its answer was a lie.
+fn row_background(row: String, is_lie: bool) -> ColoredString {
+    match is_lie {
+        true => row.on_red(),
+        false => row.on_bright_black(),
+    }
+}
+
+fn print_lies(gamestate: &Gamestate, lies: &[usize]) {
+    if !gamestate.round_over || gamestate.max_lies == 0 {
+        return;
+    }
+    let numbers: Vec<String> = lies.iter().map(|index| (index + 1).to_string()).collect();
+    match numbers.as_slice() {
+        [] => println!("Every answer was true."),
+        [number] => println!("The answer to guess {} was a lie.", number),
+        _ => println!("The answers to guesses {} were lies.", numbers.join(", ")),
+    }
+}
+
+// Positional flags: black marks an exact peg, white one present elsewhere.
+fn mark_symbol(flag: Color) -> ColoredString {
+    match flag {
+        Color::Black => "●".green(),
+        Color::White => "●".yellow(),
+        _ => "○".white(),
+    }
 }
 
 fn format_line(line: &Line) -> String {
-    line.pegs
+    let colors: Vec<Color> = line.pegs.iter().map(|peg| peg.color).collect();
+    format_colors(&colors)
+}
+
+fn format_colors(colors: &[Color]) -> String {
+    colors
         .iter()
-        .map(|peg| colored_symbol(peg.color).to_string())
+        .map(|color| colored_symbol(*color).to_string())
         .collect::<Vec<_>>()
         .join(" ")
 }
@@ -144,5 +259,10 @@ fn colored_symbol(color: Color) -> ColoredString {
         Color::Green => symbol.green(),
         Color::Blue => symbol.blue(),
         Color::Yellow => symbol.yellow(),
+        Color::Orange => symbol.truecolor(255, 140, 0),
+        Color::Purple => symbol.truecolor(140, 60, 220),
+        Color::Cyan => symbol.cyan(),
+        Color::Magenta => symbol.bright_magenta(),
+        Color::Letter(_) => format!("{:?}", color).bold(),
     }
 }
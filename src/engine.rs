@@ -0,0 +1,752 @@
+/*
+    Engine module.
+
+    Provides a headless game engine that runs Mastermind rounds without touching
+    stdin or stdout. Frontends (such as the terminal game) drive the engine and
+    decide how to read guesses and present results.
+
+    Public API:
+    - Engine::new: creates a game from a GameConfig, including a bot breaker when
+      the game mode needs one and a random target when the bot is the Code Maker.
+    - Engine::from_gamestate: resumes a game from an existing (e.g. loaded) Gamestate.
+    - Engine::submit_guess: validates and records a guess, returning the flags and Feedback.
+    - Engine::check_guess: the earlier clue a guess contradicts, if any.
+    - Engine::play_bot_turn: asks the bot breaker for its next guess and submits it.
+    - Engine::search_control: a SearchControl for the next bot turn, with the
+      time limit from the bot settings.
+    - Engine::play_bot_turn_within: play_bot_turn under a given SearchControl, so a
+      frontend can show progress and cancel the search.
+    - Engine::submit_batch / play_bot_batch / play_bot_batch_within: the batch
+      of a static round, from a human or the bot; max_batch_size is its limit.
+    - Engine::bot_guess_within / score_bot_guess: the bot's turn when the human
+      Code Maker scores it by hand; correct_answer fixes an earlier answer,
+      and declare_lie / can_declare_lie record an answer given as a lie.
+    - Engine::round_status / is_bots_turn / needs_human_target / is_human_scoring:
+      query the round.
+    - Engine::set_target: sets the secret code chosen by a human Code Maker;
+      until then guesses are refused with GuessError::NoTarget.
+    - Engine::take_hint: answers a hint for the human Code Breaker (see the hints
+      module) and counts it in the Gamestate.
+    - Engine::finish_round: computes the RoundResult and applies the Maker's score.
+    - Engine::start_next_round: swaps roles, resets the bot and picks a new target.
+    - build_bot (crate only): the built-in breaker for some bot settings.
+
+    Public types:
+    - GuessError: reasons a submitted guess can be rejected.
+    - ScoringError: reasons a hand-scored answer can be refused.
+
+    Notes:
+    - The engine owns the Gamestate and the optional bot breaker; both stay public
+      so frontends can render and save them.
+    - Engine::new reconciles the config before the bot and the makers are
+      built, keeping the warnings in config_warnings; everything after works
+      from the Gamestate.
+    - Hard mode only rejects a human's guesses; a bot's guess is never refused,
+      so a weak bot can still finish its turn.
+*/
+
+use crate::{
+    bot::{Bot, BotMode, BotSettings, Difficulty, RandomBot},
+    constraint::ConstraintBot,
+    evil::EvilMaker,
+    gameconfig::GameConfig,
+    gamelogic::{
+        Contradiction, RoundResult, calculate_round_result, check_under_rule, feedback_from_flags,
+        find_contradiction, find_repeated_color, flags_from_feedback, is_possible_feedback,
+    },
+    gamestate::{Gamestate, RoundStatus},
+    hints::{Hint, HintKind, count_consistent, reveal_peg, suggest_guess},
+    humanscore::find_suspects,
+    lies::LyingMaker,
+    sampling::{SamplingBot, uses_sampling},
+    strategy::{BreakerStrategy, SearchControl},
+    types::{CodeSpace, Color, Feedback, GameMode, Line},
+};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    RoundOver,
+    // A human Code Maker hasn't set this round's code (see set_target).
+    NoTarget,
+    WrongLength { expected: usize, found: usize },
+    ColorNotInPlay(Color),
+    // The color appears twice while repeats are ruled out.
+    RepeatedColor(Color),
+    // Hard mode: the guess can't be the target given an earlier clue.
+    Inconsistent(Contradiction),
+    // Word games: the guess spells no word of the word list.
+    NotAWord(String),
+    // Static rounds: the batch has to come first, and only once.
+    BatchExpected,
+    NoBatchExpected,
+    BatchSize { max: usize, found: usize },
+}
+
+// Reasons a hand-scored answer to the bot's guess can be refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScoringError {
+    RoundOver,
+    NoSuchGuess(usize),
+    ImpossibleFeedback { feedback: Feedback, pegs: usize },
+    // No code fits all the answers. Holds the guess numbers (1-based) whose
+    // answer alone must have been the mistake; empty if it takes two or more.
+    Inconsistent { suspects: Vec<usize> },
+    // Every lie allowed this round was already told.
+    NoLiesLeft { max: u8 },
+    WinningLie,
+}
+
+impl std::fmt::Display for ScoringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ScoringError::RoundOver => write!(f, "The round is already over."),
+            ScoringError::NoSuchGuess(number) => write!(f, "There is no guess {}.", number),
+            ScoringError::ImpossibleFeedback { feedback, pegs } => write!(
+                f,
+                "{} black and {} white can't happen with {} pegs.",
+                feedback.correct_position, feedback.correct_color, pegs
+            ),
+            ScoringError::NoLiesLeft { max } => {
+                write!(
+                    f,
+                    "No lies left; at most {} answers a round may be lies.",
+                    max
+                )
+            }
+            ScoringError::WinningLie => {
+                write!(
+                    f,
+                    "An all-black answer ends the round, so it can't be a lie."
+                )
+            }
+            ScoringError::Inconsistent { suspects } => match suspects.as_slice() {
+                [] => write!(
+                    f,
+                    "No code fits these answers; more than one must be wrong."
+                ),
+                [number] => write!(
+                    f,
+                    "No code fits these answers; the answer to guess {} must be wrong.",
+                    number
+                ),
+                numbers => {
+                    let numbers: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+                    write!(
+                        f,
+                        "No code fits these answers; one of the answers to guesses {} is wrong.",
+                        numbers.join(", ")
+                    )
+                }
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for GuessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GuessError::RoundOver => write!(f, "The round is already over."),
+            GuessError::NoTarget => write!(f, "The Code Maker hasn't set the code yet."),
+            GuessError::WrongLength { expected, found } => {
+                write!(f, "Expected {} pegs, got {}.", expected, found)
+            }
+            GuessError::ColorNotInPlay(Color::Empty) => write!(f, "Empty pegs are not allowed."),
+            GuessError::ColorNotInPlay(color) => write!(f, "{:?} is not in play.", color),
+            GuessError::RepeatedColor(Color::Empty) => {
+                write!(f, "Only one empty peg is allowed in a code.")
+            }
+            GuessError::RepeatedColor(color) => {
+                write!(
+                    f,
+                    "{:?} is used more than once; colors can't repeat.",
+                    color
+                )
+            }
+            GuessError::NotAWord(word) => write!(f, "'{}' is not in the word list.", word),
+            GuessError::Inconsistent(contradiction) => {
+                write!(f, "Hard mode: that can't be the code. {}", contradiction)
+            }
+            GuessError::BatchExpected => write!(f, "This round starts with a batch of guesses."),
+            GuessError::NoBatchExpected => write!(f, "No batch of guesses is expected now."),
+            GuessError::BatchSize { max, found } => {
+                write!(f, "A batch holds 1 to {} guesses, got {}.", max, found)
+            }
+        }
+    }
+}
+
+pub struct Engine {
+    pub gamestate: Gamestate,
+    pub bot: Option<Box<dyn BreakerStrategy>>,
+    // The settings Engine::new had to change to fit the game.
+    pub config_warnings: Vec<String>,
+    thread_pool: Option<ThreadPool>,
+    evil_maker: Option<EvilMaker>,
+    lying_maker: Option<LyingMaker>,
+}
+
+impl Engine {
+    // The config is reconciled first (see GameConfig::reconcile); what it
+    // had to change is kept in config_warnings.
+    pub fn new(cfg: &GameConfig, breaker: Option<Box<dyn BreakerStrategy>>) -> Self {
+        let (cfg, config_warnings) = cfg.clone().reconcile();
+        let no_of_pegs = cfg.pegs_in_a_line as usize;
+        let mut gamestate = Gamestate::new(
+            cfg.game_mode,
+            cfg.number_of_guesses,
+            no_of_pegs,
+            Line::empty(no_of_pegs),
+            cfg.is_empty_pegs_allowed,
+            cfg.number_of_colors as usize,
+            cfg.bot_settings,
+        );
+        gamestate.word_list = cfg.word_list;
+        gamestate.dictionary = cfg.dictionary;
+        gamestate.feedback_rule = cfg.feedback_rule;
+        gamestate.no_repeats = cfg.no_repeats;
+        gamestate.static_mode = cfg.static_mode;
+        gamestate.max_lies = cfg.max_lies;
+        gamestate.hint_penalty = cfg.hint_penalty;
+        gamestate.warn_inconsistent = cfg.warn_inconsistent;
+        gamestate.hard_mode = cfg.hard_mode;
+        gamestate.evil_maker = cfg.evil_maker;
+        gamestate.human_scored = cfg.human_scored;
+        if gamestate.needs_human_target() {
+            gamestate.target_line = Line::new(Vec::new());
+        } else if !gamestate.is_human_scored_round() {
+            gamestate.target_line = gamestate.randomize_target_line();
+        }
+
+        Engine {
+            config_warnings,
+            ..Self::from_gamestate(gamestate, breaker)
+        }
+    }
+
+    // With threads capped in the bot settings, the bot's turns run in a rayon
+    // pool of that size instead of the global one.
+    pub fn from_gamestate(gamestate: Gamestate, breaker: Option<Box<dyn BreakerStrategy>>) -> Self {
+        let bot = init_bot(&gamestate, breaker);
+        let thread_pool = gamestate.bot_settings.threads.and_then(|threads| {
+            ThreadPoolBuilder::new()
+                .num_threads(threads.max(1))
+                .build()
+                .ok()
+        });
+        let evil_maker = init_evil_maker(&gamestate);
+        let lying_maker = init_lying_maker(&gamestate);
+        Engine {
+            gamestate,
+            bot,
+            config_warnings: Vec::new(),
+            thread_pool,
+            evil_maker,
+            lying_maker,
+        }
+    }
+
+    pub fn round_status(&self) -> RoundStatus {
+        self.gamestate.round_status
+    }
+
+    pub fn is_bots_turn(&self) -> bool {
+        self.gamestate.is_it_bots_turn_to_guess()
+    }
+
+    pub fn needs_human_target(&self) -> bool {
+        self.gamestate.needs_human_target()
+    }
+
+    // True while the bot breaks a code the human Maker keeps to themselves
+    // and scores by hand.
+    pub fn is_human_scoring(&self) -> bool {
+        self.gamestate.is_human_scored_round()
+    }
+
+    pub fn set_target(&mut self, target: Line) {
+        self.gamestate.target_line = target;
+    }
+
+    // None when no human is guessing right now or the code isn't set yet,
+    // or for a reveal once every peg is revealed. Only hints actually given
+    // are counted.
+    pub fn take_hint(&mut self, kind: HintKind) -> Option<Hint> {
+        if self.gamestate.round_over || self.is_bots_turn() || !self.gamestate.has_target() {
+            return None;
+        }
+        let hint = match kind {
+            HintKind::Count => Hint::Count(count_consistent(&self.gamestate)),
+            HintKind::Suggestion => {
+                let control = self.search_control();
+                let gamestate = &self.gamestate;
+                let guess = match &self.thread_pool {
+                    Some(pool) => pool.install(|| suggest_guess(gamestate, &control)),
+                    None => suggest_guess(gamestate, &control),
+                };
+                Hint::Suggestion(guess)
+            }
+            HintKind::RevealPeg => {
+                let (position, color) = reveal_peg(&self.gamestate)?;
+                self.gamestate.revealed_pegs.push(position);
+                if let Some(maker) = self.round_evil_maker() {
+                    maker.fix_peg(position, color);
+                }
+                if let Some(maker) = self.round_lying_maker() {
+                    maker.fix_peg(position, color);
+                }
+                Hint::RevealPeg { position, color }
+            }
+        };
+        self.gamestate.hints_used = self.gamestate.hints_used.saturating_add(1);
+        Some(hint)
+    }
+
+    // A static round refuses single guesses until its batch is in.
+    pub fn submit_guess(&mut self, guess: Line) -> Result<(Line, Feedback), GuessError> {
+        if self.gamestate.round_over {
+            return Err(GuessError::RoundOver);
+        }
+        if !self.gamestate.has_target() {
+            return Err(GuessError::NoTarget);
+        }
+        if self.gamestate.is_awaiting_batch() {
+            return Err(GuessError::BatchExpected);
+        }
+        self.validate_guess(&guess)?;
+        if self.gamestate.hard_mode
+            && !self.is_bots_turn()
+            && let Some(contradiction) = self.check_guess(&guess)
+        {
+            return Err(GuessError::Inconsistent(contradiction));
+        }
+
+        let result = self.play_guess(guess);
+        self.update_round_status();
+        Ok(result)
+    }
+
+    // Plays every guess of a static round's batch before any feedback is
+    // shown; the round then waits for the final guess. Nothing is played if
+    // any guess is refused.
+    pub fn submit_batch(
+        &mut self,
+        guesses: Vec<Line>,
+    ) -> Result<Vec<(Line, Feedback)>, GuessError> {
+        if self.gamestate.round_over {
+            return Err(GuessError::RoundOver);
+        }
+        if !self.gamestate.has_target() {
+            return Err(GuessError::NoTarget);
+        }
+        if !self.gamestate.is_awaiting_batch() {
+            return Err(GuessError::NoBatchExpected);
+        }
+        let max = self.max_batch_size();
+        if guesses.is_empty() || guesses.len() > max {
+            return Err(GuessError::BatchSize {
+                max,
+                found: guesses.len(),
+            });
+        }
+        for guess in &guesses {
+            self.validate_guess(guess)?;
+        }
+
+        self.gamestate.batch_size = guesses.len();
+        let results = guesses
+            .into_iter()
+            .map(|guess| self.play_guess(guess))
+            .collect();
+        self.update_round_status();
+        Ok(results)
+    }
+
+    // Every guess but the last of the round can go in the batch.
+    pub fn max_batch_size(&self) -> usize {
+        (self.gamestate.round_length as usize).saturating_sub(1)
+    }
+
+    // The checks every guess gets, whoever plays it and whatever the phase.
+    fn validate_guess(&self, guess: &Line) -> Result<(), GuessError> {
+        if guess.pegs.len() != self.gamestate.pegs_in_a_line {
+            return Err(GuessError::WrongLength {
+                expected: self.gamestate.pegs_in_a_line,
+                found: guess.pegs.len(),
+            });
+        }
+        let palette = self.gamestate.palette();
+        if let Some(peg) = guess.pegs.iter().find(|p| !palette.contains(&p.color)) {
+            return Err(GuessError::ColorNotInPlay(peg.color));
+        }
+        if self.gamestate.no_repeats
+            && let Some(color) = find_repeated_color(guess)
+        {
+            return Err(GuessError::RepeatedColor(color));
+        }
+        if let Some(dictionary) = &self.gamestate.dictionary
+            && dictionary.index_of_line(guess).is_none()
+        {
+            return Err(GuessError::NotAWord(
+                guess.to_string().replace(' ', "").to_lowercase(),
+            ));
+        }
+        Ok(())
+    }
+
+    // A bot-made code may be held by an EvilMaker, which first moves the
+    // target to keep the most candidates alive, or a LyingMaker, which may
+    // answer falsely. Otherwise the guess is scored under the feedback rule,
+    // so the flags show only what the rule reveals.
+    fn play_guess(&mut self, guess: Line) -> (Line, Feedback) {
+        if let Some(maker) = self.round_evil_maker() {
+            self.gamestate.target_line = maker.respond(&guess);
+        }
+        if let Some(maker) = self.round_lying_maker() {
+            let (target, flags) = maker.respond(&guess);
+            self.gamestate.target_line = target;
+            let feedback = feedback_from_flags(&flags);
+            self.gamestate.guessed_lines.push(guess);
+            self.gamestate.flag_pegs.push(flags.clone());
+            return (flags, feedback);
+        }
+        let (flags, feedback) = check_under_rule(
+            &self.gamestate.target_line,
+            &guess,
+            self.gamestate.feedback_rule,
+        );
+        self.gamestate.guessed_lines.push(guess);
+        self.gamestate.flag_pegs.push(flags.clone());
+        (flags, feedback)
+    }
+
+    pub fn check_guess(&self, guess: &Line) -> Option<Contradiction> {
+        find_contradiction(&self.gamestate, guess)
+    }
+
+    pub fn search_control(&self) -> SearchControl {
+        SearchControl::new(self.gamestate.bot_settings.time_limit())
+    }
+
+    pub fn play_bot_turn(&mut self) -> Option<(Line, Feedback)> {
+        let control = self.search_control();
+        self.play_bot_turn_within(&control)
+    }
+
+    // None in a human-scored round, where the answer has to come from the
+    // human through score_bot_guess.
+    pub fn play_bot_turn_within(&mut self, control: &SearchControl) -> Option<(Line, Feedback)> {
+        if self.is_human_scoring() {
+            return None;
+        }
+        let guess = self.bot_guess_within(control)?;
+
+        let (flags, feedback) = self.submit_guess(guess.clone()).ok()?;
+        if let Some(bot) = self.bot.as_mut() {
+            bot.observe_flags(&guess, &flags);
+        }
+        Some((guess, feedback))
+    }
+
+    pub fn play_bot_batch(&mut self) -> Option<Vec<(Line, Feedback)>> {
+        let control = self.search_control();
+        self.play_bot_batch_within(&control)
+    }
+
+    // The bot's batch in a static round, with the feedback to each guess.
+    pub fn play_bot_batch_within(
+        &mut self,
+        control: &SearchControl,
+    ) -> Option<Vec<(Line, Feedback)>> {
+        if !self.gamestate.is_awaiting_batch() {
+            return None;
+        }
+        let max = self.max_batch_size();
+        let bot = self.bot.as_mut()?;
+        let batch = match &self.thread_pool {
+            Some(pool) => pool.install(|| bot.next_batch_within(max, control)),
+            None => bot.next_batch_within(max, control),
+        };
+
+        let scored = self.submit_batch(batch.clone()).ok()?;
+        if let Some(bot) = self.bot.as_mut() {
+            for (guess, (flags, _)) in batch.iter().zip(&scored) {
+                bot.observe_flags(guess, flags);
+            }
+        }
+        Some(
+            scored
+                .into_iter()
+                .zip(batch)
+                .map(|((_, feedback), guess)| (guess, feedback))
+                .collect(),
+        )
+    }
+
+    // The bot's next guess without playing it, for a human to score by hand
+    // with score_bot_guess. After a static batch this is the final guess.
+    pub fn bot_guess_within(&mut self, control: &SearchControl) -> Option<Line> {
+        if self.gamestate.round_over || self.gamestate.is_awaiting_batch() {
+            return None;
+        }
+        let is_final_guess = self.gamestate.static_mode;
+        let bot = self.bot.as_mut()?;
+        let mut next_guess = || {
+            if is_final_guess {
+                bot.final_guess_within(control)
+            } else {
+                bot.next_guess_within(control)
+            }
+        };
+        Some(match &self.thread_pool {
+            Some(pool) => pool.install(next_guess),
+            None => next_guess(),
+        })
+    }
+
+    // Records a guess with the feedback the human gave for it. The answer is
+    // refused if no code fits it together with the earlier ones; all black
+    // makes the guess the target and wins the round.
+    pub fn score_bot_guess(
+        &mut self,
+        guess: Line,
+        feedback: Feedback,
+    ) -> Result<Line, ScoringError> {
+        self.record_bot_answer(guess, feedback, false)
+    }
+
+    // The same for an answer the human gives as one of the round's lies. A
+    // lie is never checked against the other answers, but can't be all black.
+    pub fn declare_lie(&mut self, guess: Line, feedback: Feedback) -> Result<Line, ScoringError> {
+        self.record_bot_answer(guess, feedback, true)
+    }
+
+    // Whether the human may still declare a lie this round.
+    pub fn can_declare_lie(&self) -> bool {
+        self.is_human_scoring()
+            && self.gamestate.declared_lies.len() < self.gamestate.max_lies as usize
+    }
+
+    fn record_bot_answer(
+        &mut self,
+        guess: Line,
+        feedback: Feedback,
+        is_lie: bool,
+    ) -> Result<Line, ScoringError> {
+        if self.gamestate.round_over {
+            return Err(ScoringError::RoundOver);
+        }
+        let pegs = self.gamestate.pegs_in_a_line;
+        if !is_possible_feedback(feedback, pegs, self.gamestate.feedback_rule) {
+            return Err(ScoringError::ImpossibleFeedback { feedback, pegs });
+        }
+        if is_lie {
+            if !self.can_declare_lie() {
+                return Err(ScoringError::NoLiesLeft {
+                    max: self.gamestate.max_lies,
+                });
+            }
+            if feedback.correct_position as usize == pegs {
+                return Err(ScoringError::WinningLie);
+            }
+            self.gamestate
+                .declared_lies
+                .push(self.gamestate.guessed_lines.len());
+        } else {
+            let mut answers = self.answers();
+            answers.push((guess.clone(), feedback));
+            self.check_answers(&answers)?;
+        }
+
+        let flags = flags_from_feedback(feedback, pegs);
+        self.gamestate.guessed_lines.push(guess.clone());
+        self.gamestate.flag_pegs.push(flags.clone());
+        if feedback.correct_position as usize == pegs {
+            self.gamestate.target_line = guess.clone();
+        }
+        if let Some(bot) = self.bot.as_mut() {
+            bot.observe_feedback(&guess, feedback);
+        }
+        self.update_round_status();
+        Ok(flags)
+    }
+
+    // Replaces the answer to an earlier guess (1-based) and lets the bot
+    // learn the board again from scratch.
+    pub fn correct_answer(
+        &mut self,
+        guess_number: usize,
+        feedback: Feedback,
+    ) -> Result<(), ScoringError> {
+        if self.gamestate.round_over {
+            return Err(ScoringError::RoundOver);
+        }
+        let pegs = self.gamestate.pegs_in_a_line;
+        let index = guess_number
+            .checked_sub(1)
+            .filter(|index| *index < self.gamestate.guessed_lines.len())
+            .ok_or(ScoringError::NoSuchGuess(guess_number))?;
+        if !is_possible_feedback(feedback, pegs, self.gamestate.feedback_rule) {
+            return Err(ScoringError::ImpossibleFeedback { feedback, pegs });
+        }
+        let mut answers = self.answers();
+        answers[index].1 = feedback;
+        self.check_answers(&answers)?;
+
+        self.gamestate.flag_pegs[index] = flags_from_feedback(feedback, pegs);
+        if feedback.correct_position as usize == pegs {
+            self.gamestate.target_line = answers[index].0.clone();
+        }
+        if let Some(bot) = self.bot.as_mut() {
+            bot.reset_for_round();
+            for (guess, feedback) in &answers {
+                bot.observe_feedback(guess, *feedback);
+            }
+        }
+        self.update_round_status();
+        Ok(())
+    }
+
+    fn answers(&self) -> Vec<(Line, Feedback)> {
+        self.gamestate
+            .guessed_lines
+            .iter()
+            .cloned()
+            .zip(self.gamestate.flag_pegs.iter().map(feedback_from_flags))
+            .collect()
+    }
+
+    // Declared lies are false on purpose, so only the other answers have to
+    // fit a code.
+    fn check_answers(&self, answers: &[(Line, Feedback)]) -> Result<(), ScoringError> {
+        let truthful: Vec<usize> = (0..answers.len())
+            .filter(|index| !self.gamestate.declared_lies.contains(index))
+            .collect();
+        let kept: Vec<(Line, Feedback)> = truthful
+            .iter()
+            .map(|index| answers[*index].clone())
+            .collect();
+        match find_suspects(&self.gamestate.code_space(), &kept) {
+            None => Ok(()),
+            Some(suspects) => Err(ScoringError::Inconsistent {
+                suspects: suspects.iter().map(|index| truthful[*index] + 1).collect(),
+            }),
+        }
+    }
+
+    pub fn update_round_status(&mut self) {
+        self.gamestate.is_bot_guessing_this_round = self.gamestate.is_it_bots_turn_to_guess();
+        self.gamestate.update_round_status();
+        self.gamestate.round_over = self.gamestate.round_status != RoundStatus::Ongoing;
+    }
+
+    pub fn finish_round(&mut self) -> RoundResult {
+        let round_result = calculate_round_result(&self.gamestate);
+
+        self.gamestate.reset_round_statuses();
+
+        match self.gamestate.game_mode {
+            GameMode::TwoPlayer | GameMode::PlayerVsBot => {
+                self.gamestate.apply_maker_score(round_result.score_delta);
+            }
+            _ => {}
+        }
+        round_result
+    }
+
+    pub fn start_next_round(&mut self) {
+        self.gamestate.prepare_next_round();
+
+        if let Some(bot) = self.bot.as_mut() {
+            bot.reset_for_round();
+        }
+
+        if self.is_human_scoring() {
+            self.gamestate.target_line = Line::empty(self.gamestate.pegs_in_a_line);
+        } else if self.needs_human_target() {
+            self.gamestate.target_line = Line::new(Vec::new());
+        } else {
+            self.gamestate.target_line = self.gamestate.randomize_target_line();
+        }
+        if let Some(maker) = self.evil_maker.as_mut()
+            && EvilMaker::uses_evil_maker(&self.gamestate)
+        {
+            maker.reset_for_round(&self.gamestate);
+        }
+        if let Some(maker) = self.lying_maker.as_mut()
+            && LyingMaker::uses_lying_maker(&self.gamestate)
+        {
+            maker.reset_for_round(&self.gamestate);
+        }
+    }
+
+    // The makers are kept for the whole game but only hold the code in the
+    // rounds a bot makes it.
+    fn round_evil_maker(&mut self) -> Option<&mut EvilMaker> {
+        self.evil_maker
+            .as_mut()
+            .filter(|_| EvilMaker::uses_evil_maker(&self.gamestate))
+    }
+
+    fn round_lying_maker(&mut self) -> Option<&mut LyingMaker> {
+        self.lying_maker
+            .as_mut()
+            .filter(|_| LyingMaker::uses_lying_maker(&self.gamestate))
+    }
+}
+
+// Built once per game, since the space and the rules never change; each
+// round only resets the codes.
+fn init_evil_maker(gamestate: &Gamestate) -> Option<EvilMaker> {
+    if !EvilMaker::plays_in(gamestate) {
+        return None;
+    }
+    let mut maker = EvilMaker::new(gamestate.code_space())?;
+    if EvilMaker::uses_evil_maker(gamestate) {
+        maker.reset_for_round(gamestate);
+    }
+    Some(maker)
+}
+
+fn init_lying_maker(gamestate: &Gamestate) -> Option<LyingMaker> {
+    if !LyingMaker::plays_in(gamestate) {
+        return None;
+    }
+    let mut maker = LyingMaker::new(gamestate.code_space(), None)?;
+    if LyingMaker::uses_lying_maker(gamestate) {
+        maker.reset_for_round(gamestate);
+    }
+    Some(maker)
+}
+
+fn init_bot(
+    gamestate: &Gamestate,
+    breaker: Option<Box<dyn BreakerStrategy>>,
+) -> Option<Box<dyn BreakerStrategy>> {
+    let needs_bot = gamestate.game_mode == GameMode::SpectateBot
+        || gamestate.game_mode == GameMode::PlayerVsBot;
+    if !needs_bot {
+        return None;
+    }
+    breaker.or_else(|| Some(build_bot(gamestate.bot_settings, gamestate.code_space())))
+}
+
+// A RandomBot at the Random difficulty, a SamplingBot in Sampling mode or
+// when the space is too large to enumerate, a ConstraintBot in Constraint
+// mode (unless lies or words need the enumerating Bot), and a Bot otherwise.
+pub(crate) fn build_bot(settings: BotSettings, space: CodeSpace) -> Box<dyn BreakerStrategy> {
+    if settings.difficulty == Difficulty::Random {
+        Box::new(RandomBot::with_space(space))
+    } else if uses_sampling(&settings, &space) {
+        Box::new(SamplingBot::with_space(space, settings))
+    } else if settings.mode == BotMode::Constraint
+        && settings.difficulty == Difficulty::Full
+        && space.max_lies == 0
+        && space.dictionary.is_none()
+    {
+        Box::new(ConstraintBot::new(space.palette, space.width, settings))
+    } else {
+        Box::new(Bot::with_space(space, settings))
+    }
+}
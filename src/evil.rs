@@ -0,0 +1,112 @@
+/*
+    Evil maker module.
+
+    An adversarial Code Maker for games where the bot makes the code and a
+    human breaks it. It never commits to a code: every guess is answered with
+    the feedback that leaves the most codes consistent with the board, and the
+    code revealed at the end is one that fits everything it answered.
+
+    Public API:
+    - EvilMaker::new: a maker for a CodeSpace, or None when the space is too
+      large to enumerate.
+    - EvilMaker::reset_for_round: starts over on a Gamestate's round,
+      replaying the guesses, flags and revealed pegs already on its board
+      (e.g. after loading).
+    - EvilMaker::respond: answers a guess and returns the code it now
+      pretends to hold.
+    - EvilMaker::fix_peg: keeps only codes with a revealed peg.
+    - EvilMaker::plays_in / uses_evil_maker: whether a Gamestate's game, or
+      its current round, has one.
+
+    Notes:
+    - The remaining codes and their partitions come from a Bot, so the maker
+      has the same size limit as the enumerating bot (MAX_ENUMERATED_SPACE).
+      Larger games keep a random target instead.
+    - The code returned by respond is always consistent with every answer so
+      far, so the engine stores it as the target and the usual scoring, win
+      check and hints keep working unchanged.
+    - Ties prefer any answer over all-correct, so a guess only wins once it is
+      the last code left.
+    - With lies allowed the LyingMaker (see the lies module) plays the evil
+      maker instead.
+    - Answers follow the game's feedback rule, so under the positional rule
+      the maker picks the marks of each peg, not just the counts.
+*/
+
+use crate::{
+    bot::{Bot, BotSettings},
+    gamestate::Gamestate,
+    sampling::MAX_ENUMERATED_SPACE,
+    types::{Code, CodeSpace, Color, GameMode, Line},
+};
+
+use rand::{rng, seq::IndexedRandom};
+
+pub struct EvilMaker {
+    bot: Bot,
+}
+
+impl EvilMaker {
+    pub fn new(space: CodeSpace) -> Option<Self> {
+        space
+            .count()
+            .filter(|count| *count <= MAX_ENUMERATED_SPACE)?;
+        Some(EvilMaker {
+            bot: Bot::with_space(space, BotSettings::default()),
+        })
+    }
+
+    // The bot and its feedback table are kept; only the codes start over.
+    pub fn reset_for_round(&mut self, gamestate: &Gamestate) {
+        self.bot.reset_for_new_round();
+        for (guess, flags) in gamestate.guessed_lines.iter().zip(&gamestate.flag_pegs) {
+            self.bot.observe_flags(guess, flags);
+        }
+        for position in &gamestate.revealed_pegs {
+            self.fix_peg(*position, gamestate.target_line.pegs[*position].color);
+        }
+    }
+
+    pub fn respond(&mut self, guess: &Line) -> Line {
+        let answer = self.bot.largest_partition(guess);
+        self.bot.observe_answer(guess, answer);
+        self.current_code()
+    }
+
+    pub fn fix_peg(&mut self, position: usize, color: Color) {
+        let space = self.bot.space.clone();
+        let Some(digit) = space.index_of(color) else {
+            return;
+        };
+        let mut digits = vec![0; space.width];
+        self.bot.retain_solutions(|code| {
+            space.digits(code, &mut digits);
+            digits[position] as usize == digit
+        });
+    }
+
+    // Only human Code Breakers face the evil maker: Practice, and Player vs
+    // Bot while the player is breaking.
+    pub fn plays_in(gamestate: &Gamestate) -> bool {
+        gamestate.evil_maker
+            && gamestate.max_lies == 0
+            && matches!(
+                gamestate.game_mode,
+                GameMode::Practice | GameMode::PlayerVsBot
+            )
+    }
+
+    pub fn uses_evil_maker(gamestate: &Gamestate) -> bool {
+        Self::plays_in(gamestate)
+            && (gamestate.game_mode == GameMode::Practice || gamestate.p1s_turn)
+    }
+
+    fn current_code(&self) -> Line {
+        let code: Code = *self
+            .bot
+            .possible_solutions
+            .choose(&mut rng())
+            .expect("no code fits the evil maker's answers");
+        self.bot.space.unrank(code)
+    }
+}
@@ -0,0 +1,89 @@
+/*
+    Feedback table module.
+
+    Precomputes the feedback between every pair of codes in a small search
+    space so the bot can replace repeated scoring with a table lookup.
+
+    Public API:
+    - FeedbackTable::build: builds the table for a CodeSpace, or returns None
+      when colors^pegs squared is too large to keep in memory.
+    - FeedbackTable::build_answers: the same from any answer packed into a
+      number below the space's answer_count (see CodeSpace::answer), e.g. the
+      per-position marks of the positional rule.
+    - FeedbackTable::get: looks up the feedback of a guess against a target.
+    - FeedbackTable::get_encoded: same, as its Feedback::encode value (or the
+      packed answer of a table from build_answers).
+
+    Notes:
+    - Entries are stored as u8, so the table is only built for spaces with
+      at most MAX_TABLE_ANSWERS answers (up to 15 pegs, or 5 under the
+      positional rule).
+    - MAX_TABLE_ENTRIES caps the table at 64 MiB (covers 4x6, 5x6, 4x7, 4x8...).
+    - Building is parallelised with rayon, one row (target) per task.
+*/
+
+use crate::types::{Code, CodeSpace, Feedback, FeedbackRule};
+
+use rayon::prelude::*;
+
+const MAX_TABLE_ENTRIES: u64 = 1 << 26;
+const MAX_TABLE_ANSWERS: usize = 1 << 8;
+
+pub struct FeedbackTable {
+    size: usize,
+    width: usize,
+    entries: Vec<u8>,
+}
+
+impl FeedbackTable {
+    pub fn build(space: &CodeSpace, score: impl Fn(Code, Code) -> Feedback + Sync) -> Option<Self> {
+        let width = space.width;
+        let counting = CodeSpace {
+            feedback_rule: FeedbackRule::Standard,
+            ..space.clone()
+        };
+        Self::build_answers(&counting, |target, guess| {
+            score(target, guess).encode(width)
+        })
+    }
+
+    pub fn build_answers(
+        space: &CodeSpace,
+        answer: impl Fn(Code, Code) -> usize + Sync,
+    ) -> Option<Self> {
+        if space.answer_count() > MAX_TABLE_ANSWERS {
+            return None;
+        }
+        let size = space.size()?;
+        if size.checked_mul(size)? > MAX_TABLE_ENTRIES {
+            return None;
+        }
+
+        let size = size as usize;
+        let width = space.width;
+        let mut entries = vec![0; size * size];
+        entries
+            .par_chunks_mut(size)
+            .enumerate()
+            .for_each(|(target, row)| {
+                for (guess, entry) in row.iter_mut().enumerate() {
+                    *entry = answer(target as Code, guess as Code) as u8;
+                }
+            });
+
+        Some(FeedbackTable {
+            size,
+            width,
+            entries,
+        })
+    }
+
+    pub fn get(&self, target: Code, guess: Code) -> Feedback {
+        Feedback::decode(self.get_encoded(target, guess), self.width)
+    }
+
+    // The Feedback::encode value, for callers that bucket feedback by number.
+    pub fn get_encoded(&self, target: Code, guess: Code) -> usize {
+        self.entries[target as usize * self.size + guess as usize] as usize
+    }
+}
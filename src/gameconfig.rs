@@ -5,9 +5,23 @@
     a configuration file.
 
     Public API:
-    - GameConfig: holds all user-configurable parameters.
+    - GameConfig: holds all user-configurable parameters; its Default is what
+      missing keys fall back to.
     - GameConfig::load_from_file: reads a config file, applies defaults,
-      parses key/value pairs, and returns a populated GameConfig.
+      parses key/value pairs, and returns a populated GameConfig with the
+      warnings about the settings it had to ignore or change.
+    - GameConfig::reconcile: drops the rules that don't fit the game, with a
+      warning for each; load_from_file and Engine::new both call it.
+    - parse_game_mode: parses a string input into a GameMode enum if valid.
+    - parse_feedback_rule: parses the feedback rule (standard, black_only or
+      positional).
+    - is_valid_number_of_colors: checks a palette size against the supported range.
+    - fits_lies: whether a game is small enough to be played with lies.
+    - fits_without_repeats: whether codes of some width can avoid repeating a
+      color (pegs no more than the colors, counting empty pegs if allowed).
+    - parse_max_lies: parses how many answers per round may be false.
+    - parse_time_limit: parses the bot's thinking time in seconds (0 or "none"
+      for no limit) into milliseconds.
 
     Internal helpers (private):
     - parse_bool: converts common string forms ("true", "yes", "1", "on") to bool.
@@ -16,23 +30,87 @@
     - Lines may contain comments; everything after '#' is ignored.
     - Missing or invalid values fall back to safe defaults.
     - Unknown keys are ignored but reported.
+    - Nothing is printed here; the warnings are returned for the frontend
+      to show.
+    - no_repeated_colors is turned off, with a warning, when the pegs
+      outnumber the symbols in play.
+    - static_mode needs at least two guesses (a batch and the final guess),
+      and turns human_scored off: the bot's batch is scored by the engine.
+    - feedback=positional falls back to standard above MAX_POSITIONAL_WIDTH
+      pegs, and also turns human_scored off.
+    - word_list names a word file and switches to the word variant; colors
+      and empty pegs are then not used. A list without words of
+      pegs_in_a_line letters plays with colors, and a bot mode other than
+      Classic or Knuth plays as Knuth, both with a warning.
+    - max_lies is turned off, with a warning, in static mode and for games
+      with more than MAX_ENUMERATED_SPACE codes, since the bot then can't
+      track how many lies each code needs.
 */
 
-use crate::{manualconfig::parse_game_mode, types::GameMode};
+use crate::{
+    bot::{BotMode, BotSettings, parse_bot_mode, parse_difficulty, parse_heuristic, plays_words},
+    gamestate::DEFAULT_HINT_PENALTY,
+    sampling::MAX_ENUMERATED_SPACE,
+    types::{
+        CodeSpace, Color, DEFAULT_COLORS, FeedbackRule, GameMode, MAX_COLORS, MAX_POSITIONAL_WIDTH,
+    },
+    words::Dictionary,
+};
 
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct GameConfig {
     pub game_mode: GameMode,
     pub number_of_guesses: u8,
     pub pegs_in_a_line: u8,
+    pub number_of_colors: u8,
     pub is_empty_pegs_allowed: bool,
+    pub bot_settings: BotSettings,
+    pub hint_penalty: u8,
+    pub warn_inconsistent: bool,
+    pub hard_mode: bool,
+    pub evil_maker: bool,
+    pub human_scored: bool,
+    pub no_repeats: bool,
+    pub static_mode: bool,
+    pub feedback_rule: FeedbackRule,
+    pub max_lies: u8,
+    // Plays the word variant with the words in this file (see the words module).
+    pub word_list: Option<String>,
+    // Its words, once reconcile has read them.
+    pub dictionary: Option<Arc<Dictionary>>,
+}
+
+// The values used for any key missing from the config file.
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            game_mode: GameMode::Practice,
+            number_of_guesses: 10,
+            pegs_in_a_line: 4,
+            number_of_colors: DEFAULT_COLORS as u8,
+            is_empty_pegs_allowed: false,
+            bot_settings: BotSettings::default(),
+            hint_penalty: DEFAULT_HINT_PENALTY,
+            warn_inconsistent: false,
+            hard_mode: false,
+            evil_maker: false,
+            human_scored: false,
+            no_repeats: false,
+            static_mode: false,
+            feedback_rule: FeedbackRule::Standard,
+            max_lies: 0,
+            word_list: None,
+            dictionary: None,
+        }
+    }
 }
 
 impl GameConfig {
-    pub fn load_from_file(filename: &str) -> Option<Self> {
+    pub fn load_from_file(filename: &str) -> Option<(Self, Vec<String>)> {
         if !Path::new(filename).exists() {
             return None;
         }
@@ -40,10 +118,24 @@ impl GameConfig {
         let content = fs::read_to_string(filename).ok()?;
 
         // Default values in case a line is missing
-        let mut game_mode = GameMode::Practice;
-        let mut guesses = 10;
-        let mut pegs = 4;
-        let mut empty = false;
+        let defaults = GameConfig::default();
+        let mut game_mode = defaults.game_mode;
+        let mut guesses = defaults.number_of_guesses;
+        let mut pegs = defaults.pegs_in_a_line;
+        let mut colors = defaults.number_of_colors;
+        let mut empty = defaults.is_empty_pegs_allowed;
+        let mut bot_settings = defaults.bot_settings;
+        let mut hint_penalty = defaults.hint_penalty;
+        let mut warn_inconsistent = defaults.warn_inconsistent;
+        let mut hard_mode = defaults.hard_mode;
+        let mut evil_maker = defaults.evil_maker;
+        let mut human_scored = defaults.human_scored;
+        let mut no_repeats = defaults.no_repeats;
+        let mut static_mode = defaults.static_mode;
+        let mut feedback_rule = defaults.feedback_rule;
+        let mut max_lies = defaults.max_lies;
+        let mut word_list = defaults.word_list;
+        let mut warnings = Vec::new();
 
         for line in content.lines() {
             // Remove comments and whitespace
@@ -59,37 +151,286 @@ impl GameConfig {
                 match key {
                     "game_mode" => game_mode = parse_game_mode(value).unwrap(),
                     "number_of_guesses" => {
-                        if let Ok(v) = value.parse::<u8>() {
-                            if v > 0 {
-                                guesses = v;
-                            }
+                        if let Ok(v) = value.parse::<u8>()
+                            && v > 0
+                        {
+                            guesses = v;
                         }
                     }
                     "pegs_in_a_line" => {
-                        if let Ok(v) = value.parse::<u8>() {
-                            if v > 0 {
-                                pegs = v;
-                            }
+                        if let Ok(v) = value.parse::<u8>()
+                            && v > 0
+                        {
+                            pegs = v;
+                        }
+                    }
+                    "colors" => {
+                        if let Ok(v) = value.parse::<u8>()
+                            && is_valid_number_of_colors(v)
+                        {
+                            colors = v;
                         }
                     }
                     "include_empty_pegs" => {
                         empty = parse_bool(value);
                     }
+                    "bot_mode" => {
+                        if let Some(mode) = parse_bot_mode(value) {
+                            bot_settings.mode = mode;
+                        }
+                    }
+                    "heuristic" => {
+                        if let Some(heuristic) = parse_heuristic(value) {
+                            bot_settings.heuristic = heuristic;
+                        }
+                    }
+                    "hint_penalty" => {
+                        if let Ok(v) = value.parse::<u8>() {
+                            hint_penalty = v;
+                        }
+                    }
+                    "warn_inconsistent_guesses" => {
+                        warn_inconsistent = parse_bool(value);
+                    }
+                    "hard_mode" => {
+                        hard_mode = parse_bool(value);
+                    }
+                    "evil_maker" => {
+                        evil_maker = parse_bool(value);
+                    }
+                    "human_scored" => {
+                        human_scored = parse_bool(value);
+                    }
+                    "no_repeated_colors" => {
+                        no_repeats = parse_bool(value);
+                    }
+                    "static_mode" => {
+                        static_mode = parse_bool(value);
+                    }
+                    "feedback" => {
+                        if let Some(rule) = parse_feedback_rule(value) {
+                            feedback_rule = rule;
+                        }
+                    }
+                    "max_lies" => {
+                        if let Some(lies) = parse_max_lies(value) {
+                            max_lies = lies;
+                        }
+                    }
+                    "word_list" => {
+                        word_list = (!value.is_empty()).then(|| value.to_string());
+                    }
+                    "bot_difficulty" => {
+                        if let Some(difficulty) = parse_difficulty(value) {
+                            bot_settings.difficulty = difficulty;
+                        }
+                    }
+                    "bot_time_limit" => {
+                        if let Some(limit) = parse_time_limit(value) {
+                            bot_settings.time_limit_ms = limit;
+                        }
+                    }
+                    "bot_threads" => {
+                        if let Ok(v) = value.parse::<usize>() {
+                            bot_settings.threads = (v > 0).then_some(v);
+                        }
+                    }
                     _ => {
-                        println!("Unknown key '{}' in config, ignoring...", key);
+                        warnings.push(format!("Unknown key '{}' in config, ignoring...", key));
                     }
                 }
             }
         }
 
-        println!("Loaded configuration from {}!", filename);
-        Some(GameConfig {
-            game_mode: game_mode,
+        let cfg = GameConfig {
+            game_mode,
             number_of_guesses: guesses,
             pegs_in_a_line: pegs,
+            number_of_colors: colors,
             is_empty_pegs_allowed: empty,
-        })
+            bot_settings,
+            hint_penalty,
+            warn_inconsistent,
+            hard_mode,
+            evil_maker,
+            human_scored,
+            no_repeats,
+            static_mode,
+            feedback_rule,
+            max_lies,
+            word_list,
+            dictionary: None,
+        };
+        let (cfg, rule_warnings) = cfg.reconcile();
+        warnings.extend(rule_warnings);
+        Some((cfg, warnings))
+    }
+
+    // The config with every rule that doesn't fit the game dropped, and a
+    // warning for each. Reads the word list unless its words are already in
+    // `dictionary`; the codes of a word game are its words, so the checks on
+    // colors don't apply to it. Settling a settled config changes nothing.
+    pub fn reconcile(mut self) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let pegs = self.pegs_in_a_line;
+        let is_loaded = self
+            .dictionary
+            .as_ref()
+            .is_some_and(|dictionary| dictionary.length() == pegs as usize);
+        if !is_loaded {
+            let loaded = self
+                .word_list
+                .as_deref()
+                .map(|path| Dictionary::load(path, pegs as usize).map(Arc::new));
+            self.dictionary = match loaded {
+                Some(Err(error)) => {
+                    warnings.push(format!("{} Playing with colors.", error));
+                    self.word_list = None;
+                    None
+                }
+                loaded => loaded.and_then(Result::ok),
+            };
+        }
+        if self.word_list.is_none() {
+            self.dictionary = None;
+        }
+        let palette = match &self.dictionary {
+            Some(_) => Color::letters(),
+            None => Color::palette(self.number_of_colors as usize, self.is_empty_pegs_allowed),
+        };
+        let space = CodeSpace::new(palette, pegs as usize).with_dictionary(self.dictionary.clone());
+
+        if self.dictionary.is_some() && !plays_words(self.bot_settings.mode) {
+            warnings.push(format!(
+                "Word games have no {} bot; playing as Knuth.",
+                self.bot_settings.mode
+            ));
+            self.bot_settings.mode = BotMode::Knuth;
+        }
+        if self.no_repeats && space.clone().with_no_repeats(true).count() == Some(0) {
+            warnings.push(match self.dictionary {
+                Some(_) => format!(
+                    "Every word of {} letters repeats a letter; allowing repeated letters.",
+                    pegs
+                ),
+                None => format!(
+                    "{} pegs can't all differ with {} symbols; allowing repeated colors.",
+                    pegs,
+                    self.number_of_colors as usize + self.is_empty_pegs_allowed as usize
+                ),
+            });
+            self.no_repeats = false;
+        }
+        if self.static_mode && self.number_of_guesses < 2 {
+            warnings.push(
+                "Static mode needs at least 2 guesses; playing one guess at a time.".to_string(),
+            );
+            self.static_mode = false;
+        }
+        if self.static_mode && self.human_scored {
+            warnings.push(
+                "Static mode can't be scored by hand; the game scores the bot's guesses."
+                    .to_string(),
+            );
+            self.human_scored = false;
+        }
+        if self.feedback_rule == FeedbackRule::Positional && pegs as usize > MAX_POSITIONAL_WIDTH {
+            warnings.push(format!(
+                "Positional feedback needs at most {} pegs; using standard feedback.",
+                MAX_POSITIONAL_WIDTH
+            ));
+            self.feedback_rule = FeedbackRule::Standard;
+        }
+        if self.feedback_rule == FeedbackRule::Positional && self.human_scored {
+            warnings.push(
+                "Positional feedback can't be scored by hand; the game scores the bot's guesses."
+                    .to_string(),
+            );
+            self.human_scored = false;
+        }
+        if self.max_lies > 0 && self.static_mode {
+            warnings
+                .push("Static mode can't be played with lies; every answer is true.".to_string());
+            self.max_lies = 0;
+        }
+        let fits_lies = space
+            .with_no_repeats(self.no_repeats)
+            .count()
+            .is_some_and(|count| count <= MAX_ENUMERATED_SPACE);
+        if self.max_lies > 0 && !fits_lies {
+            warnings.push(format!(
+                "Lies need at most {} possible codes; every answer is true.",
+                MAX_ENUMERATED_SPACE
+            ));
+            self.max_lies = 0;
+        }
+        (self, warnings)
+    }
+}
+
+pub fn is_valid_number_of_colors(colors: u8) -> bool {
+    (2..=MAX_COLORS as u8).contains(&colors)
+}
+
+pub fn fits_without_repeats(pegs: u8, colors: u8, is_empty_allowed: bool) -> bool {
+    pegs as usize <= colors as usize + is_empty_allowed as usize
+}
+
+// Whether the bot can track lies in this game: it has to list every code.
+pub fn fits_lies(pegs: u8, colors: u8, is_empty_allowed: bool, no_repeats: bool) -> bool {
+    CodeSpace::new(
+        Color::palette(colors as usize, is_empty_allowed),
+        pegs as usize,
+    )
+    .with_no_repeats(no_repeats)
+    .count()
+    .is_some_and(|count| count <= MAX_ENUMERATED_SPACE)
+}
+
+pub fn parse_max_lies(value: &str) -> Option<u8> {
+    match value.trim().to_lowercase().as_str() {
+        "none" | "off" | "no" => Some(0),
+        value => value.parse::<u8>().ok(),
+    }
+}
+
+pub fn parse_feedback_rule(value: &str) -> Option<FeedbackRule> {
+    match value.trim().to_lowercase().as_str() {
+        "s" | "standard" | "1" => Some(FeedbackRule::Standard),
+        "b" | "black" | "black_only" | "black-only" | "2" => Some(FeedbackRule::BlackOnly),
+        "p" | "positional" | "wordle" | "3" => Some(FeedbackRule::Positional),
+        _ => None,
+    }
+}
+
+pub fn parse_game_mode(value: &str) -> Option<GameMode> {
+    let mode = value.trim().to_lowercase();
+
+    match mode.as_str() {
+        "p" | "practice" => Some(GameMode::Practice),
+
+        "2" | "pvp" | "2p" | "two" | "two_player" | "twoplayer" | "2-player" => {
+            Some(GameMode::TwoPlayer)
+        }
+
+        "b" | "pvb" | "vsbot" | "player_vs_bot" | "player-vs-bot" => Some(GameMode::PlayerVsBot),
+        "s" | "spectate" | "spectate_bot" => Some(GameMode::SpectateBot),
+        _ => None,
+    }
+}
+
+// Seconds (fractions allowed) to milliseconds; 0 or "none" means no limit.
+pub fn parse_time_limit(value: &str) -> Option<Option<u64>> {
+    let value = value.trim().to_lowercase();
+    if value == "none" || value == "off" {
+        return Some(None);
+    }
+    let seconds = value.parse::<f64>().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
     }
+    let ms = (seconds * 1000.0).round() as u64;
+    Some((ms > 0).then_some(ms))
 }
 
 fn parse_bool(value: &str) -> bool {
@@ -1,15 +1,16 @@
 /* This file contains the game logic functions */
 
 use crate::{
-    bot::{Bot, reset_bot_for_new_round},
     gamestate::Gamestate,
-    parse::{continue_playing, get_validated_line_input},
-    prints::print_win_or_loss,
-    savegame::autosave,
-    twoplayer::{get_human_target_line, two_player_end_of_round_score_and_prints},
-    types::{Color, Feedback, GameMode, Line},
+    types::{Color, Feedback, FeedbackRule, LETTERS, Line, MAX_COLORS},
 };
 
+use std::fmt;
+
+// Upper bound on distinct symbols (palette indices or Color::symbol values)
+// the histogram scoring can count.
+pub const MAX_SYMBOLS: usize = 1 + MAX_COLORS + LETTERS;
+
 pub fn check_for_matches(target: &Line, guess: &Line) -> (Line, Feedback) {
     let width = target.pegs.len();
     let mut flags = Line::empty(width);
@@ -20,22 +21,22 @@ pub fn check_for_matches(target: &Line, guess: &Line) -> (Line, Feedback) {
         correct_color: 0,
     };
 
-    for i in 0..width {
+    for (i, used) in target_used.iter_mut().enumerate() {
         if guess.pegs[i].color == target.pegs[i].color {
             // Hard match
             flags.pegs[flag_index].color = Color::Black;
             flag_index += 1;
-            target_used[i] = true;
+            *used = true;
             feedback.correct_position += 1;
         }
     }
     for i in 0..width {
         if guess.pegs[i].color != target.pegs[i].color {
-            for j in 0..width {
-                if !target_used[j] && guess.pegs[i].color == target.pegs[j].color {
+            for (j, used) in target_used.iter_mut().enumerate() {
+                if !*used && guess.pegs[i].color == target.pegs[j].color {
                     flags.pegs[flag_index].color = Color::White;
                     flag_index += 1;
-                    target_used[j] = true;
+                    *used = true;
                     feedback.correct_color += 1;
                     break;
                 }
@@ -45,101 +46,286 @@ pub fn check_for_matches(target: &Line, guess: &Line) -> (Line, Feedback) {
     (flags, feedback)
 }
 
-pub fn human_guess(gamestate: &mut Gamestate) {
-    print!(
-        "Enter {} colors (or 'empty') separated by spaces: ",
-        gamestate.pegs_in_a_line
-    );
-
-    // Get validated guess line
-    let line = get_validated_line_input(&gamestate);
+// Same feedback as check_for_matches, computed from per-color histograms
+// without building flags or allocating.
+pub fn score_guess(target: &Line, guess: &Line) -> Feedback {
+    score_symbols(
+        target.pegs.iter().map(|peg| peg.color.symbol()),
+        guess.pegs.iter().map(|peg| peg.color.symbol()),
+    )
+}
 
-    // Update Gamestate
-    gamestate.guessed_lines.push(line);
-    let (flags, _) = check_for_matches(
-        &gamestate.target_line,
-        gamestate.guessed_lines.last().unwrap(),
-    );
-    gamestate.flag_pegs.push(flags);
+// score_guess for lines given as palette indices (e.g. unpacked Codes).
+pub fn score_digits(target: &[u8], guess: &[u8]) -> Feedback {
+    score_symbols(
+        target.iter().map(|d| *d as usize),
+        guess.iter().map(|d| *d as usize),
+    )
 }
 
-pub enum LoopAction {
-    Continue,
-    Break,
+// Reads the feedback back from a row of flag pegs (black = right position,
+// white = right color).
+pub fn feedback_from_flags(flags: &Line) -> Feedback {
+    let count = |color| flags.pegs.iter().filter(|peg| peg.color == color).count() as u8;
+    Feedback {
+        correct_position: count(Color::Black),
+        correct_color: count(Color::White),
+    }
 }
 
-enum TargetProvider {
-    Human,
-    Bot,
+// The flag pegs check_for_matches would show for a feedback: blacks first,
+// then whites, then empty.
+pub fn flags_from_feedback(feedback: Feedback, width: usize) -> Line {
+    let mut flags = Line::empty(width);
+    let blacks = feedback.correct_position as usize;
+    let whites = feedback.correct_color as usize;
+    for (i, peg) in flags.pegs.iter_mut().take(blacks + whites).enumerate() {
+        peg.color = if i < blacks {
+            Color::Black
+        } else {
+            Color::White
+        };
+    }
+    flags
 }
 
-pub struct RoundResult {
-    pub guesses_used: u8,
-    pub is_win: bool,
-    pub bonus: u8,
-    pub score_delta: u8,
+// Per-position marks for lines given as palette indices, packed in base 3
+// with the first peg most significant: 2 exact, 1 present elsewhere, 0
+// absent. A color is marked present only as often as the target has it
+// unmatched, left to right, so the counts agree with score_digits.
+pub fn mark_digits(target: &[u8], guess: &[u8]) -> usize {
+    let mut unmatched = [0u16; MAX_SYMBOLS];
+    for (t, g) in target.iter().zip(guess) {
+        if t != g {
+            unmatched[*t as usize] += 1;
+        }
+    }
+    guess.iter().zip(target).fold(0, |marks, (g, t)| {
+        let mark = if g == t {
+            2
+        } else if unmatched[*g as usize] > 0 {
+            unmatched[*g as usize] -= 1;
+            1
+        } else {
+            0
+        };
+        marks * 3 + mark
+    })
 }
 
-fn calculate_round_result(gamestate: &Gamestate) -> RoundResult {
-    let guesses_used = gamestate.guessed_lines.len() as u8;
-    let is_win = gamestate.check_for_win();
-    let bonus = if !is_win { 1 } else { 0 };
+// The flag pegs of the positional rule: black under an exact peg, white
+// under one present elsewhere, empty under an absent one.
+pub fn mark_positions(target: &Line, guess: &Line) -> Line {
+    let to_symbols = |line: &Line| -> Vec<u8> {
+        line.pegs
+            .iter()
+            .map(|peg| peg.color.symbol() as u8)
+            .collect()
+    };
+    flags_from_marks(
+        mark_digits(&to_symbols(target), &to_symbols(guess)),
+        target.pegs.len(),
+    )
+}
 
-    RoundResult {
-        guesses_used,
-        is_win,
-        bonus,
-        score_delta: guesses_used + bonus,
+pub fn flags_from_marks(mut marks: usize, width: usize) -> Line {
+    let mut flags = Line::empty(width);
+    for peg in flags.pegs.iter_mut().rev() {
+        peg.color = match marks % 3 {
+            2 => Color::Black,
+            1 => Color::White,
+            _ => Color::Empty,
+        };
+        marks /= 3;
     }
+    flags
 }
 
-pub fn handle_end_of_round(gamestate: &mut Gamestate, bot: &mut Option<Bot>) -> LoopAction {
-    autosave(gamestate).ok();
+pub fn marks_from_flags(flags: &Line) -> usize {
+    flags.pegs.iter().fold(0, |marks, peg| {
+        marks * 3
+            + match peg.color {
+                Color::Black => 2,
+                Color::White => 1,
+                _ => 0,
+            }
+    })
+}
+
+// Whether some code could answer a guess with this feedback: the counts fit
+// in the line, all but one peg right never leaves a single misplaced one,
+// and the rule reveals every count given.
+pub fn is_possible_feedback(feedback: Feedback, width: usize, rule: FeedbackRule) -> bool {
+    let blacks = feedback.correct_position as usize;
+    let whites = feedback.correct_color as usize;
+    blacks + whites <= width
+        && !(width > 0 && blacks == width - 1 && whites == 1)
+        && rule.apply(feedback) == feedback
+}
 
-    let round_result = calculate_round_result(gamestate);
+// check_for_matches as the Code Breaker sees it under a feedback rule; the
+// flags only show what the rule reveals, and under the positional rule they
+// stand under the pegs they mark.
+pub fn check_under_rule(target: &Line, guess: &Line, rule: FeedbackRule) -> (Line, Feedback) {
+    let feedback = rule.apply(score_guess(target, guess));
+    let flags = match rule {
+        FeedbackRule::Positional => mark_positions(target, guess),
+        _ => flags_from_feedback(feedback, target.pegs.len()),
+    };
+    (flags, feedback)
+}
 
-    gamestate.reset_round_statuses();
+// The first color used twice in a line, if any; codes and guesses may not
+// have one when repeats are ruled out.
+pub fn find_repeated_color(line: &Line) -> Option<Color> {
+    line.pegs
+        .iter()
+        .enumerate()
+        .find(|(i, peg)| line.pegs[..*i].contains(peg))
+        .map(|(_, peg)| peg.color)
+}
 
-    print_win_or_loss(&gamestate, &round_result);
+// An earlier clue that a guess could not have produced if it were the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contradiction {
+    pub guess_number: usize, // 1-based, as shown on the board
+    pub clue: Feedback,
+    pub would_score: Feedback,
+    // Under the positional rule: the marks the guess got and would have got.
+    pub marks: Option<(Line, Line)>,
+}
 
-    match gamestate.game_mode {
-        GameMode::TwoPlayer | GameMode::PlayerVsBot => {
-            two_player_end_of_round_score_and_prints(gamestate, &round_result);
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((clue, would_mark)) = &self.marks {
+            return write!(
+                f,
+                "Guess {} was marked {}, but if this were the code it would have been marked {}.",
+                self.guess_number,
+                describe_marks(clue),
+                describe_marks(would_mark)
+            );
         }
-        _ => {}
+        write!(
+            f,
+            "Guess {} got {} black and {} white, but if this were the code it would have got {} black and {} white.",
+            self.guess_number,
+            self.clue.correct_position,
+            self.clue.correct_color,
+            self.would_score.correct_position,
+            self.would_score.correct_color
+        )
     }
+}
 
-    if !continue_playing() {
-        return LoopAction::Break;
+// The first clue on the board that rules the line out as the target, if any.
+// With lies allowed a line may contradict up to max_lies clues, as long as
+// none of them is a win it would fake or hide.
+pub fn find_contradiction(gamestate: &Gamestate, line: &Line) -> Option<Contradiction> {
+    let width = gamestate.pegs_in_a_line as u8;
+    let contradictions: Vec<Contradiction> = gamestate
+        .guessed_lines
+        .iter()
+        .zip(&gamestate.flag_pegs)
+        .enumerate()
+        .filter_map(|(i, (guess, flags))| {
+            let clue = feedback_from_flags(flags);
+            let (would_mark, would_score) = check_under_rule(line, guess, gamestate.feedback_rule);
+            let marks = (gamestate.feedback_rule == FeedbackRule::Positional)
+                .then(|| (flags.clone(), would_mark));
+            let is_contradiction = match &marks {
+                Some((clue, would_mark)) => clue != would_mark,
+                None => would_score != clue,
+            };
+            is_contradiction.then_some(Contradiction {
+                guess_number: i + 1,
+                clue,
+                would_score,
+                marks,
+            })
+        })
+        .collect();
+    let is_win = |contradiction: &Contradiction| {
+        contradiction.clue.correct_position == width
+            || contradiction.would_score.correct_position == width
+    };
+    if contradictions.len() > gamestate.max_lies as usize || contradictions.iter().any(is_win) {
+        contradictions.into_iter().next()
+    } else {
+        None
     }
+}
 
-    gamestate.prepare_next_round();
+// Positional marks in words, first peg first: "exact, absent, present, ...".
+fn describe_marks(flags: &Line) -> String {
+    flags
+        .pegs
+        .iter()
+        .map(|peg| match peg.color {
+            Color::Black => "exact",
+            Color::White => "present",
+            _ => "absent",
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    match who_picks_target(gamestate) {
-        TargetProvider::Human => {
-            gamestate.target_line = get_human_target_line(gamestate);
-        }
-        TargetProvider::Bot => {
-            gamestate.target_line = gamestate.randomize_target_line();
+fn score_symbols(
+    target: impl Iterator<Item = usize>,
+    guess: impl Iterator<Item = usize>,
+) -> Feedback {
+    let mut target_counts = [0u16; MAX_SYMBOLS];
+    let mut guess_counts = [0u16; MAX_SYMBOLS];
+    let mut correct_position: u16 = 0;
+
+    for (t, g) in target.zip(guess) {
+        if t == g {
+            correct_position += 1;
         }
+        target_counts[t] += 1;
+        guess_counts[g] += 1;
     }
 
-    reset_bot_for_new_round(bot, gamestate);
+    // Every color shared by both lines matches min(count in target, count in guess)
+    // times; the exact-position matches are part of that total.
+    let total_matches: u16 = target_counts
+        .iter()
+        .zip(guess_counts.iter())
+        .map(|(t, g)| *t.min(g))
+        .sum();
 
-    LoopAction::Continue
+    Feedback {
+        correct_position: correct_position as u8,
+        correct_color: (total_matches - correct_position) as u8,
+    }
 }
 
-fn who_picks_target(gamestate: &Gamestate) -> TargetProvider {
-    match gamestate.game_mode {
-        GameMode::TwoPlayer => TargetProvider::Human,
-        GameMode::PlayerVsBot => {
-            if gamestate.p1s_turn {
-                TargetProvider::Bot
-            } else {
-                TargetProvider::Human
-            }
-        }
-        GameMode::Practice => TargetProvider::Bot,
-        GameMode::SpectateBot => TargetProvider::Bot,
+pub struct RoundResult {
+    pub guesses_used: u8,
+    pub is_win: bool,
+    pub bonus: u8,
+    pub hints_used: u8,
+    pub hint_penalty: u8,
+    pub score_delta: u8,
+}
+
+// The Code Maker scores one point per guess, one bonus point if the code
+// wasn't solved, and the hint penalty for every hint the Breaker took.
+pub fn calculate_round_result(gamestate: &Gamestate) -> RoundResult {
+    let guesses_used = gamestate.guessed_lines.len() as u8;
+    let is_win = gamestate.check_for_win();
+    let bonus = if !is_win { 1 } else { 0 };
+    let hints_used = gamestate.hints_used;
+    let hint_penalty = hints_used.saturating_mul(gamestate.hint_penalty);
+
+    RoundResult {
+        guesses_used,
+        is_win,
+        bonus,
+        hints_used,
+        hint_penalty,
+        score_delta: guesses_used
+            .saturating_add(bonus)
+            .saturating_add(hint_penalty),
     }
 }
@@ -0,0 +1,183 @@
+/*
+    Game loop module.
+
+    Terminal frontend glue between the headless Engine and the console: reads
+    human guesses and walks the players through the end of each round.
+
+    Public API:
+    - human_guess: prompts the player for a guess and submits it to the engine,
+      answering any hints asked for along the way and warning about guesses
+      that contradict earlier clues when that option is on.
+    - human_batch: reads the batch of guesses a static round starts with.
+    - human_scores_bot_guess: shows the bot's guess to a human Code Maker who
+      keeps the code secret, reads their answer (asking whether it is a lie
+      while lies are left) and helps fix a wrong one.
+    - handle_end_of_round: autosaves, prints results, asks whether to continue
+      and prepares the next round.
+
+    Public types:
+    - LoopAction: indicates whether the main game loop should continue or break.
+
+    Notes:
+    - All rule handling lives in the engine; this module only does input and output.
+*/
+
+use crate::{
+    parse::{
+        GuessInput, ask_is_lie, continue_playing, get_batch_input, get_feedback_input,
+        get_guess_input, get_guess_number_to_fix,
+    },
+    prints::{
+        print_bot_guess, print_contradiction_warning, print_hint, print_round_summary,
+        print_win_or_loss,
+    },
+    savegame::autosave,
+    twoplayer::get_human_target_line,
+};
+
+use mastermind::{
+    engine::{Engine, ScoringError},
+    types::{FeedbackRule, GameMode, Line},
+};
+
+pub fn human_guess(engine: &mut Engine) {
+    if engine.gamestate.static_mode {
+        print!("Final guess. ");
+    }
+    if engine.gamestate.is_word_game() {
+        print!(
+            "Enter a word of {} letters, or 'hint': ",
+            engine.gamestate.pegs_in_a_line
+        );
+    } else {
+        print!(
+            "Enter {} colors (or 'empty') separated by spaces, or 'hint': ",
+            engine.gamestate.pegs_in_a_line
+        );
+    }
+
+    // Get validated guess line, answering hints until a guess comes in
+    let line = loop {
+        match get_guess_input(&engine.gamestate) {
+            GuessInput::Guess(line) => break line,
+            GuessInput::Hint(kind) => {
+                let hint = engine.take_hint(kind);
+                print_hint(hint.as_ref(), engine.gamestate.hints_used);
+                print!("Your guess: ");
+            }
+        }
+    };
+
+    // Hard mode rejects the guess in submit_guess, so only warn outside it
+    if engine.gamestate.warn_inconsistent
+        && !engine.gamestate.hard_mode
+        && let Some(contradiction) = engine.check_guess(&line)
+    {
+        print_contradiction_warning(&contradiction);
+    }
+
+    // Update Gamestate
+    if let Err(e) = engine.submit_guess(line) {
+        println!("{}", e);
+    }
+}
+
+pub fn human_batch(engine: &mut Engine) {
+    let max = engine.max_batch_size();
+    let symbols = match engine.gamestate.is_word_game() {
+        true => "letters",
+        false => "colors",
+    };
+    println!(
+        "Static round: enter up to {} guesses of {} {}, one per line, and an empty line to finish.",
+        max, engine.gamestate.pegs_in_a_line, symbols
+    );
+    println!("You'll see all their feedback together, then make one final guess.");
+    loop {
+        let batch = get_batch_input(&engine.gamestate, max);
+        match engine.submit_batch(batch) {
+            Ok(_) => return,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+pub fn human_scores_bot_guess(engine: &mut Engine, guess: Line) {
+    let guess_number = engine.gamestate.guessed_lines.len() + 1;
+    print_bot_guess(guess_number, &guess);
+
+    // A corrected earlier answer can end the round, so check before each try
+    while !engine.gamestate.round_over {
+        match engine.gamestate.feedback_rule {
+            FeedbackRule::BlackOnly => print!("How many black pegs? "),
+            _ => print!("How many black and white pegs? (e.g. '2 1'): "),
+        }
+        let feedback = get_feedback_input(engine.gamestate.feedback_rule);
+        let lies_left = engine.gamestate.max_lies as usize - engine.gamestate.declared_lies.len();
+        let result = if engine.can_declare_lie() && ask_is_lie(lies_left) {
+            engine.declare_lie(guess.clone(), feedback)
+        } else {
+            engine.score_bot_guess(guess.clone(), feedback)
+        };
+        match result {
+            Ok(_) => return,
+            Err(e @ ScoringError::Inconsistent { .. }) => {
+                println!("{}", e);
+                fix_earlier_answer(engine, guess_number);
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+// Lets the Maker correct an earlier answer; picking the current guess just
+// scores it again.
+fn fix_earlier_answer(engine: &mut Engine, current: usize) {
+    let number = get_guess_number_to_fix(current);
+    if number == current {
+        return;
+    }
+    let rule = engine.gamestate.feedback_rule;
+    match rule {
+        FeedbackRule::BlackOnly => print!("How many black pegs for guess {}? ", number),
+        _ => print!("How many black and white pegs for guess {}? ", number),
+    }
+    let feedback = get_feedback_input(rule);
+    match engine.correct_answer(number, feedback) {
+        Ok(()) => println!("Answer to guess {} corrected.", number),
+        Err(e) => println!("{}", e),
+    }
+}
+
+pub enum LoopAction {
+    Continue,
+    Break,
+}
+
+pub fn handle_end_of_round(engine: &mut Engine) -> LoopAction {
+    autosave(&mut engine.gamestate).ok();
+
+    let round_result = engine.finish_round();
+
+    print_win_or_loss(&engine.gamestate, &round_result);
+
+    match engine.gamestate.game_mode {
+        GameMode::TwoPlayer | GameMode::PlayerVsBot => {
+            print_round_summary(&engine.gamestate, &round_result);
+        }
+        _ => {}
+    }
+
+    if !continue_playing() {
+        return LoopAction::Break;
+    }
+
+    engine.start_next_round();
+
+    if engine.needs_human_target() {
+        let target = get_human_target_line(&engine.gamestate);
+        engine.set_target(target);
+    }
+
+    LoopAction::Continue
+}
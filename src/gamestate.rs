@@ -30,10 +30,17 @@
       color-only matches (white).
 */
 
-use crate::types::{Color, GameMode, Line};
+use crate::{
+    bot::BotSettings,
+    types::{CodeSpace, Color, DEFAULT_COLORS, FeedbackRule, GameMode, Line},
+    words::{Dictionary, DictionaryError},
+};
 
-use rand::Rng;
+use rand::{Rng, seq::IndexedRandom};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub const DEFAULT_HINT_PENALTY: u8 = 1;
 
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum RoundStatus {
@@ -49,6 +56,44 @@ pub struct Gamestate {
     pub round_length: u8,
     pub is_empty_allowed: bool,
     pub pegs_in_a_line: usize,
+    #[serde(default = "default_number_of_colors")]
+    pub number_of_colors: usize,
+    #[serde(default)]
+    pub bot_settings: BotSettings,
+    // Points the Code Maker gains per hint the Code Breaker takes.
+    #[serde(default = "default_hint_penalty")]
+    pub hint_penalty: u8,
+    // Checks of a human's guess against the clues already on the board.
+    #[serde(default)]
+    pub warn_inconsistent: bool,
+    #[serde(default)]
+    pub hard_mode: bool,
+    // The bot's codes for a human breaker are made by the adversarial EvilMaker.
+    #[serde(default)]
+    pub evil_maker: bool,
+    // In Player vs Bot the human Maker keeps the code secret and scores the
+    // bot's guesses by hand.
+    #[serde(default)]
+    pub human_scored: bool,
+    // Codes and guesses never repeat a color (Bulls and Cows).
+    #[serde(default)]
+    pub no_repeats: bool,
+    // Static Mastermind: the Code Breaker plays a batch of guesses at once,
+    // sees all their feedback together and then makes one final guess.
+    #[serde(default)]
+    pub static_mode: bool,
+    // How much of each answer the Code Breaker is told.
+    #[serde(default)]
+    pub feedback_rule: FeedbackRule,
+    // How many of the Code Maker's answers in a round may be false.
+    #[serde(default)]
+    pub max_lies: u8,
+    // Word games: the word list file, saved so a loaded game reads it again.
+    #[serde(default)]
+    pub word_list: Option<String>,
+    // Its words, once load_dictionary has read them.
+    #[serde(skip)]
+    pub dictionary: Option<Arc<Dictionary>>,
 
     // Round specific
     pub target_line: Line,
@@ -61,6 +106,16 @@ pub struct Gamestate {
     pub round_status: RoundStatus,
     pub round_over: bool,
     pub is_bot_guessing_this_round: bool,
+    #[serde(default)]
+    pub hints_used: u8,
+    #[serde(default)]
+    pub revealed_pegs: Vec<usize>,
+    // Guesses in this round's static batch; 0 until it is played.
+    #[serde(default)]
+    pub batch_size: usize,
+    // Answers (0-based guess indices) a human scoring by hand declared false.
+    #[serde(default)]
+    pub declared_lies: Vec<usize>,
 }
 
 impl Gamestate {
@@ -70,13 +125,28 @@ impl Gamestate {
         pegs_in_a_line: usize,
         target_line: Line,
         is_empty_allowed: bool,
+        number_of_colors: usize,
+        bot_settings: BotSettings,
     ) -> Self {
         Gamestate {
-            game_mode: game_mode,
+            game_mode,
             round_length: number_of_guesses,
-            is_empty_allowed: is_empty_allowed,
-            pegs_in_a_line: pegs_in_a_line,
-            target_line: target_line,
+            is_empty_allowed,
+            pegs_in_a_line,
+            number_of_colors,
+            bot_settings,
+            hint_penalty: DEFAULT_HINT_PENALTY,
+            warn_inconsistent: false,
+            hard_mode: false,
+            evil_maker: false,
+            human_scored: false,
+            no_repeats: false,
+            static_mode: false,
+            feedback_rule: FeedbackRule::Standard,
+            max_lies: 0,
+            word_list: None,
+            dictionary: None,
+            target_line,
             guessed_lines: Vec::new(),
             flag_pegs: Vec::new(),
             p1_score: 0,
@@ -86,15 +156,50 @@ impl Gamestate {
             round_status: RoundStatus::Ongoing,
             round_over: false,
             is_bot_guessing_this_round: false,
+            hints_used: 0,
+            revealed_pegs: Vec::new(),
+            batch_size: 0,
+            declared_lies: Vec::new(),
         }
     }
 
+    // Read from the flags rather than the target, so a human-scored round
+    // (whose target the game never sees) is won by an all-black answer.
     pub fn check_for_win(&self) -> bool {
-        self.guessed_lines.contains(&self.target_line)
+        self.flag_pegs.iter().any(|flags| {
+            !flags.pegs.is_empty() && flags.pegs.iter().all(|peg| peg.color == Color::Black)
+        })
+    }
+
+    // The bot is breaking a code the human Maker keeps secret and scores by hand.
+    pub fn is_human_scored_round(&self) -> bool {
+        self.human_scored && self.game_mode == GameMode::PlayerVsBot && !self.p1s_turn
+    }
+
+    // A human Code Maker types in the code this round.
+    pub fn needs_human_target(&self) -> bool {
+        match self.game_mode {
+            GameMode::TwoPlayer => true,
+            GameMode::PlayerVsBot => !self.p1s_turn && !self.human_scored,
+            GameMode::Practice | GameMode::SpectateBot => false,
+        }
     }
 
+    // False while a human Code Maker hasn't typed in this round's code.
+    pub fn has_target(&self) -> bool {
+        self.target_line.pegs.len() == self.pegs_in_a_line
+    }
+
+    // A static round also ends with the one guess after the batch.
     pub fn check_for_loss(&self) -> bool {
-        self.guessed_lines.len() >= self.round_length as usize
+        let is_final_guess_used =
+            self.static_mode && self.batch_size > 0 && self.guessed_lines.len() > self.batch_size;
+        is_final_guess_used || self.guessed_lines.len() >= self.round_length as usize
+    }
+
+    // A static round whose batch hasn't been played yet.
+    pub fn is_awaiting_batch(&self) -> bool {
+        self.static_mode && self.batch_size == 0 && !self.round_over
     }
 
     pub fn reset_round_statuses(&mut self) {
@@ -102,9 +207,22 @@ impl Gamestate {
         self.round_over = false;
     }
 
+    pub fn apply_maker_score(&mut self, score_delta: u8) {
+        // Update Maker's score; hint penalties can take it to the top of a u8
+        if self.p1s_turn {
+            self.p2_score = self.p2_score.saturating_add(score_delta);
+        } else {
+            self.p1_score = self.p1_score.saturating_add(score_delta);
+        }
+    }
+
     pub fn prepare_next_round(&mut self) {
         self.guessed_lines.clear();
         self.flag_pegs.clear();
+        self.hints_used = 0;
+        self.revealed_pegs.clear();
+        self.batch_size = 0;
+        self.declared_lies.clear();
         self.current_round += 1;
 
         // Swap turns
@@ -134,33 +252,69 @@ impl Gamestate {
         }
     }
 
+    pub fn is_word_game(&self) -> bool {
+        self.dictionary.is_some()
+    }
+
+    // Reads the words of word_list, or forgets them when it is None.
+    pub fn load_dictionary(&mut self) -> Result<(), DictionaryError> {
+        self.dictionary = match &self.word_list {
+            Some(path) => Some(Arc::new(Dictionary::load(path, self.pegs_in_a_line)?)),
+            None => None,
+        };
+        Ok(())
+    }
+
+    // The letters in a word game.
+    pub fn palette(&self) -> Vec<Color> {
+        if self.is_word_game() {
+            return Color::letters();
+        }
+        Color::palette(self.number_of_colors, self.is_empty_allowed)
+    }
+
+    // The codes in play under this game's rules.
+    pub fn code_space(&self) -> CodeSpace {
+        CodeSpace::new(self.palette(), self.pegs_in_a_line)
+            .with_no_repeats(self.no_repeats)
+            .with_feedback_rule(self.feedback_rule)
+            .with_max_lies(self.max_lies)
+            .with_dictionary(self.dictionary.clone())
+    }
+
     pub fn randomize_target_line(&self) -> Line {
         let mut rng = rand::rng();
+        if self.is_word_game() {
+            let space = self.code_space();
+            let word = *space
+                .codes()
+                .choose(&mut rng)
+                .expect("the word list has no word to play");
+            return space.unrank(word);
+        }
+        let palette = self.palette();
+        if self.no_repeats {
+            return Line::new(
+                palette
+                    .choose_multiple(&mut rng, self.pegs_in_a_line)
+                    .copied()
+                    .collect(),
+            );
+        }
         let mut line = Line::empty(self.pegs_in_a_line);
 
-        for i in 0..self.pegs_in_a_line {
-            let color = if self.is_empty_allowed {
-                match rng.random_range(0..7) {
-                    0 => Color::White,
-                    1 => Color::Black,
-                    2 => Color::Red,
-                    3 => Color::Green,
-                    4 => Color::Blue,
-                    5 => Color::Yellow,
-                    _ => Color::Empty,
-                }
-            } else {
-                match rng.random_range(0..6) {
-                    0 => Color::White,
-                    1 => Color::Black,
-                    2 => Color::Red,
-                    3 => Color::Green,
-                    4 => Color::Blue,
-                    _ => Color::Yellow,
-                }
-            };
-            line.pegs[i].color = color;
+        for peg in line.pegs.iter_mut() {
+            peg.color = palette[rng.random_range(0..palette.len())];
         }
         line
     }
 }
+
+fn default_hint_penalty() -> u8 {
+    DEFAULT_HINT_PENALTY
+}
+
+// Saves made before the palette was configurable always used the base six colors.
+fn default_number_of_colors() -> usize {
+    DEFAULT_COLORS
+}
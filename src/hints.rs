@@ -0,0 +1,142 @@
+/*
+    Hints module.
+
+    Computes the help a human Code Breaker can ask for during a round. The
+    engine hands these out through Engine::take_hint, which also counts them
+    so the round's scoring can charge for them.
+
+    Public API:
+    - HintKind: which hint is asked for (Count, Suggestion, RevealPeg).
+    - Hint: the answer to a hint.
+    - parse_hint_kind: parses a hint name typed at the guess prompt.
+    - hint_command: the hint name in a line typed at the guess prompt, if
+      the line asks for a hint rather than making a guess.
+    - count_consistent: how many codes still fit every guess on the board.
+    - suggest_guess: the bot's recommended next guess for the board.
+    - reveal_peg: one peg of the target that wasn't revealed yet.
+
+    Notes:
+    - With lies allowed a code is counted while it needs at most max_lies
+      false answers (and no faked or hidden win) to fit the board.
+    - Counting needs to look at every code, so it is only done for search
+      spaces up to MAX_ENUMERATED_SPACE codes; larger boards get None.
+    - The suggestion comes from a fresh full-strength bot with the game's bot
+      mode and heuristic, replaying the board. It may take as long as a bot
+      turn, and honours the bot's time limit the same way.
+    - Revealed pegs are remembered in the Gamestate so every reveal hint
+      shows a different peg.
+*/
+
+use crate::{
+    assistant::recommend_guess,
+    gamestate::Gamestate,
+    sampling::MAX_ENUMERATED_SPACE,
+    strategy::SearchControl,
+    types::{Color, Line},
+};
+
+use rand::{rng, seq::IndexedRandom};
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HintKind {
+    Count,
+    Suggestion,
+    RevealPeg,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Hint {
+    // None when the space is too large to count.
+    Count(Option<u64>),
+    Suggestion(Line),
+    RevealPeg { position: usize, color: Color },
+}
+
+pub fn parse_hint_kind(value: &str) -> Option<HintKind> {
+    match value.trim().to_lowercase().as_str() {
+        "c" | "count" | "left" => Some(HintKind::Count),
+        "s" | "suggest" | "suggestion" => Some(HintKind::Suggestion),
+        "r" | "reveal" | "peg" => Some(HintKind::RevealPeg),
+        _ => None,
+    }
+}
+
+// "hint" has to be a word of its own, so guesses like "hints" stay guesses,
+// and a word game's guess spelled "hint" is played rather than asked for.
+pub fn hint_command<'a>(gamestate: &Gamestate, input: &'a str) -> Option<&'a str> {
+    let input = input.trim();
+    if let Some(dictionary) = &gamestate.dictionary
+        && dictionary.find(input).is_ok()
+    {
+        return None;
+    }
+    let mut words = input.splitn(2, char::is_whitespace);
+    match words.next() {
+        Some(word) if word.eq_ignore_ascii_case("hint") => Some(words.next().unwrap_or("")),
+        _ => None,
+    }
+}
+
+pub fn count_consistent(gamestate: &Gamestate) -> Option<u64> {
+    let space = gamestate.code_space();
+    space
+        .count()
+        .filter(|count| *count <= MAX_ENUMERATED_SPACE)?;
+    let width = space.width;
+    let to_digits = |line: &Line| {
+        let mut digits = vec![0; width];
+        space.digits(space.rank(line), &mut digits);
+        digits
+    };
+    let clues: Vec<(Vec<u8>, usize)> = gamestate
+        .guessed_lines
+        .iter()
+        .zip(&gamestate.flag_pegs)
+        .map(|(guess, flags)| (to_digits(guess), space.answer_of_flags(flags)))
+        .collect();
+
+    let win = space.win_answer();
+    let count = space
+        .codes()
+        .into_par_iter()
+        .filter(|code| {
+            let mut digits = vec![0; width];
+            space.digits(*code, &mut digits);
+            let mut lies = 0;
+            clues.iter().all(|(guess, answer)| {
+                let would_answer = space.answer(&digits, guess);
+                if would_answer == *answer {
+                    return true;
+                }
+                lies += 1;
+                would_answer != win && *answer != win && lies <= space.max_lies
+            })
+        })
+        .count();
+    Some(count as u64)
+}
+
+pub fn suggest_guess(gamestate: &Gamestate, control: &SearchControl) -> Line {
+    let answers: Vec<(Line, Line)> = gamestate
+        .guessed_lines
+        .iter()
+        .cloned()
+        .zip(gamestate.flag_pegs.iter().cloned())
+        .collect();
+    recommend_guess(
+        gamestate.bot_settings,
+        gamestate.code_space(),
+        &answers,
+        control,
+    )
+}
+
+// A random position not revealed yet, or None once every peg was revealed.
+pub fn reveal_peg(gamestate: &Gamestate) -> Option<(usize, Color)> {
+    let hidden: Vec<usize> = (0..gamestate.pegs_in_a_line)
+        .filter(|position| !gamestate.revealed_pegs.contains(position))
+        .collect();
+    let position = *hidden.choose(&mut rng())?;
+    Some((position, gamestate.target_line.pegs[position].color))
+}
@@ -0,0 +1,93 @@
+/*
+    Human-scored module.
+
+    Checks the answers a human Code Maker types in when they keep the code to
+    themselves (on a physical board or in their head) and score the bot's
+    guesses by hand. The engine uses it to refuse an answer that no code could
+    have produced together with the earlier ones, and to say which answer must
+    have been the mistake.
+
+    Public API:
+    - find_suspects: None while some code of a CodeSpace fits every answer (or
+      the space is too large to check); otherwise the answers that, taken back on their
+      own, make the board consistent again.
+
+    Notes:
+    - Checking walks the whole search space, so it is only done for spaces up
+      to MAX_ENUMERATED_SPACE codes. Larger games accept every answer, and the
+      sampling and constraint bots fall back to a random code when nothing fits.
+    - An empty list of suspects means at least two answers are wrong.
+*/
+
+use crate::{
+    sampling::MAX_ENUMERATED_SPACE,
+    types::{Code, CodeSpace, Feedback, Line},
+};
+
+use rayon::prelude::*;
+
+// Each answer is a guess with the feedback the human gave for it; suspects
+// are indices into `answers`.
+pub fn find_suspects(space: &CodeSpace, answers: &[(Line, Feedback)]) -> Option<Vec<usize>> {
+    space
+        .count()
+        .filter(|count| *count <= MAX_ENUMERATED_SPACE)?;
+    let no_of_pegs = space.width;
+    let answers: Vec<(Vec<u8>, Feedback)> = answers
+        .iter()
+        .map(|(guess, feedback)| {
+            let mut digits = vec![0; no_of_pegs];
+            space.digits(space.rank(guess), &mut digits);
+            (digits, *feedback)
+        })
+        .collect();
+
+    // Per code: the one answer it breaks, or None if it breaks none. Codes
+    // breaking two or more answers can't clear a single mistake.
+    let broken_by_code = |code: Code| -> Option<Option<usize>> {
+        let mut digits = vec![0; no_of_pegs];
+        space.digits(code, &mut digits);
+        let mut broken = None;
+        for (i, (guess, feedback)) in answers.iter().enumerate() {
+            if space.score(&digits, guess) != *feedback {
+                if broken.is_some() {
+                    return None;
+                }
+                broken = Some(i);
+            }
+        }
+        Some(broken)
+    };
+
+    let is_suspect = space
+        .codes()
+        .into_par_iter()
+        .filter_map(broken_by_code)
+        .fold(
+            || Some(vec![false; answers.len()]),
+            |is_suspect, broken| {
+                let mut is_suspect = is_suspect?;
+                match broken {
+                    None => return None,
+                    Some(i) => is_suspect[i] = true,
+                }
+                Some(is_suspect)
+            },
+        )
+        .reduce(
+            || Some(vec![false; answers.len()]),
+            |a, b| {
+                let (mut a, b) = (a?, b?);
+                a.iter_mut().zip(b).for_each(|(a, b)| *a |= b);
+                Some(a)
+            },
+        )?;
+    Some(
+        is_suspect
+            .into_iter()
+            .enumerate()
+            .filter(|(_, is_suspect)| *is_suspect)
+            .map(|(i, _)| i)
+            .collect(),
+    )
+}
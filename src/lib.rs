@@ -0,0 +1,46 @@
+/*
+    Mastermind library crate.
+
+    Exposes the rules, game state, bot and a headless engine so the game can be
+    embedded in other tools. Nothing in this crate reads stdin or writes stdout
+    while playing; the terminal game in main.rs is one frontend built on it.
+
+    Modules:
+    - types: core datatypes (Color, Peg, Line, Feedback, GameMode).
+    - gamelogic: scoring rules (check_for_matches) and round results.
+    - gamestate: Gamestate and RoundStatus.
+    - gameconfig: GameConfig and config file loading.
+    - strategy: the BreakerStrategy trait for pluggable code breakers.
+    - bot: the minimax code-breaking Bot (a BreakerStrategy).
+    - feedbacktable: precomputed feedback lookups for small search spaces.
+    - optimal: exhaustive solver for the minimum expected number of guesses.
+    - constraint: ConstraintBot, a backtracking breaker for wide codes.
+    - sampling: SamplingBot, a breaker for spaces too large to enumerate.
+    - symmetry: color/position symmetries that survive the guesses so far.
+    - evil: EvilMaker, an adversarial Code Maker that never commits to a code.
+    - lies: LyingMaker and the rules for answers that may be false.
+    - humanscore: checks answers a human Code Maker scores by hand.
+    - assistant: SolveAssistant, help for a game played somewhere else.
+    - hints: hints for a human Code Breaker (codes left, a suggestion, a peg).
+    - words: the Dictionary of the word variant, whose words are the codes.
+    - engine: Engine, the headless API tying it all together.
+*/
+
+pub mod assistant;
+pub mod bot;
+pub mod constraint;
+pub mod engine;
+pub mod evil;
+pub mod feedbacktable;
+pub mod gameconfig;
+pub mod gamelogic;
+pub mod gamestate;
+pub mod hints;
+pub mod humanscore;
+pub mod lies;
+pub mod optimal;
+pub mod sampling;
+pub mod strategy;
+pub mod symmetry;
+pub mod types;
+pub mod words;
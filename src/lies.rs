@@ -0,0 +1,184 @@
+/*
+    Lies module.
+
+    Rules for games where up to max_lies of the Code Maker's answers in a
+    round may be false (Ulam's searching game with lies). A bot Maker picks
+    its lies adversarially; a human scoring by hand declares them.
+
+    Public API:
+    - LyingMaker::new: a maker for a CodeSpace holding a given target or,
+      without one, an evil maker that never commits to a code. None when the
+      space is too large to enumerate.
+    - LyingMaker::reset_for_round: starts over on a Gamestate's round with
+      its target, replaying the guesses, flags and revealed pegs already on
+      its board (e.g. after loading).
+    - LyingMaker::respond: answers a guess, returning the code it now holds
+      and the flag pegs it shows, which may be a lie.
+    - LyingMaker::fix_peg: keeps only codes with a revealed peg.
+    - LyingMaker::plays_in / uses_lying_maker: whether a Gamestate's game,
+      or its current round, has one.
+    - find_lies: the answers on the board that were lies, for the end of
+      the round.
+
+    Notes:
+    - A guess that is the code is always answered with a win, and no other
+      guess ever is: lies never fake or hide a win.
+    - The maker keeps a Bot over the space, so it sees the codes the way the
+      breaker bot does: every code still possible with the number of lies its
+      answers would take. Every answer is the one with the most weight left
+      (see Bot::largest_partition), so a lie is told whenever the truth would
+      leave the breaker less to search.
+    - With a fixed target the maker may only lie while the target's own lie
+      count is below max_lies, so the code revealed at the end needs no more
+      lies than allowed. An evil maker picks any answer and reveals a code
+      that fits the board with the fewest lies.
+    - A bot Maker with lies plays for human and bot breakers alike, so a
+      Spectate Bot game shows the breaker bot against the lies.
+*/
+
+use crate::{
+    bot::{Bot, BotSettings},
+    gamelogic::check_under_rule,
+    gamestate::Gamestate,
+    sampling::MAX_ENUMERATED_SPACE,
+    types::{Code, CodeSpace, Color, GameMode, Line},
+};
+
+use rand::{rng, seq::IndexedRandom};
+
+pub struct LyingMaker {
+    bot: Bot,
+    // None for an evil maker.
+    target: Option<Code>,
+}
+
+impl LyingMaker {
+    pub fn new(space: CodeSpace, target: Option<&Line>) -> Option<Self> {
+        space
+            .count()
+            .filter(|count| *count <= MAX_ENUMERATED_SPACE)?;
+        Some(LyingMaker {
+            target: target.map(|target| space.rank(target)),
+            bot: Bot::with_space(space, BotSettings::default()),
+        })
+    }
+
+    // The bot and its feedback table are kept; only the codes and the
+    // target start over. An evil maker has no target.
+    pub fn reset_for_round(&mut self, gamestate: &Gamestate) {
+        self.bot.reset_for_new_round();
+        self.target = (!gamestate.evil_maker).then(|| self.bot.space.rank(&gamestate.target_line));
+        for (guess, flags) in gamestate.guessed_lines.iter().zip(&gamestate.flag_pegs) {
+            self.bot.observe_flags(guess, flags);
+        }
+        for position in &gamestate.revealed_pegs {
+            self.fix_peg(*position, gamestate.target_line.pegs[*position].color);
+        }
+    }
+
+    // (the code the maker holds, the flags it answers with)
+    pub fn respond(&mut self, guess: &Line) -> (Line, Line) {
+        let answer = match self.target {
+            None => self.bot.largest_partition(guess),
+            Some(target) => {
+                let space = &self.bot.space;
+                let win = space.win_answer();
+                let truth = space.answer_of_flags(
+                    &check_under_rule(&space.unrank(target), guess, space.feedback_rule).0,
+                );
+                if truth == win || self.lies_left() == 0 {
+                    truth
+                } else {
+                    self.bot
+                        .largest_partition_where(guess, |answer| answer != win)
+                }
+            }
+        };
+        self.bot.observe_answer(guess, answer);
+        (self.current_code(), self.bot.space.flags_of_answer(answer))
+    }
+
+    pub fn fix_peg(&mut self, position: usize, color: Color) {
+        let space = self.bot.space.clone();
+        let Some(digit) = space.index_of(color) else {
+            return;
+        };
+        let mut digits = vec![0; space.width];
+        self.bot.retain_solutions(|code| {
+            space.digits(code, &mut digits);
+            digits[position] as usize == digit
+        });
+    }
+
+    // A bot makes the code: Practice, Player vs Bot while the player is
+    // breaking, and Spectate Bot.
+    pub fn plays_in(gamestate: &Gamestate) -> bool {
+        gamestate.max_lies > 0 && gamestate.game_mode != GameMode::TwoPlayer
+    }
+
+    pub fn uses_lying_maker(gamestate: &Gamestate) -> bool {
+        Self::plays_in(gamestate)
+            && (gamestate.game_mode != GameMode::PlayerVsBot || gamestate.p1s_turn)
+    }
+
+    // Lies the fixed target may still tell.
+    fn lies_left(&self) -> u8 {
+        let Some(target) = self.target else {
+            return 0;
+        };
+        let index = self
+            .bot
+            .possible_solutions
+            .binary_search(&target)
+            .expect("the target fits the lying maker's answers");
+        self.bot.space.max_lies - self.bot.lie_counts[index]
+    }
+
+    fn current_code(&self) -> Line {
+        let code = match self.target {
+            Some(target) => target,
+            None => {
+                let fewest = *self
+                    .bot
+                    .lie_counts
+                    .iter()
+                    .min()
+                    .expect("no code fits the lying maker's answers");
+                let codes: Vec<Code> = self
+                    .bot
+                    .possible_solutions
+                    .iter()
+                    .zip(&self.bot.lie_counts)
+                    .filter(|(_, lies)| **lies == fewest)
+                    .map(|(code, _)| *code)
+                    .collect();
+                *codes
+                    .choose(&mut rng())
+                    .expect("a code has the fewest lies")
+            }
+        };
+        self.bot.space.unrank(code)
+    }
+}
+
+// Indices of the answers on the board that were lies: the ones a human
+// declared in a hand-scored round, otherwise those the target wouldn't have
+// given. Only meaningful once the round is over and the target is final.
+pub fn find_lies(gamestate: &Gamestate) -> Vec<usize> {
+    if gamestate.max_lies == 0 {
+        return Vec::new();
+    }
+    if gamestate.is_human_scored_round() {
+        return gamestate.declared_lies.clone();
+    }
+    gamestate
+        .guessed_lines
+        .iter()
+        .zip(&gamestate.flag_pegs)
+        .enumerate()
+        .filter(|(_, (guess, flags))| {
+            check_under_rule(&gamestate.target_line, guess, gamestate.feedback_rule).0 != **flags
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
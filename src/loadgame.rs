@@ -16,16 +16,19 @@
     - Filenames are matched without the ".json" extension.
     - User input is validated against existing save files; the prompt loops until
       a valid save is chosen.
+    - A saved word game only keeps the name of its word list; the list is read
+      again on load, and a save whose list can't be read is refused.
 */
 
-use crate::{gamestate::Gamestate, savegame::SAVE_DIR};
+use crate::savegame::SAVE_DIR;
+
+use mastermind::gamestate::Gamestate;
 
 use std::fs;
 use std::io;
 use std::io::Write;
 
 pub fn handle_load() -> Gamestate {
-    let mut save_name = String::new();
     println!("Type the name of your save to load it.");
     println!("Saves: ");
 
@@ -40,20 +43,17 @@ pub fn handle_load() -> Gamestate {
 
         let save_input = input.trim().to_string();
 
-        for save in list_save_files() {
-            if save == save_input {
-                save_name = save_input.clone();
-                break;
-            }
+        if !list_save_files().contains(&save_input) {
+            println!("No save with name: {}. Try again.", save_input);
+            continue;
         }
-        if !save_name.is_empty() {
-            break;
+        let mut gamestate = load_game(&save_input).unwrap();
+        // A word game reads its word list again
+        match gamestate.load_dictionary() {
+            Ok(()) => return gamestate,
+            Err(error) => println!("{} Choose another save.", error),
         }
-        println!("No save with name: {}. Try again.", save_input);
     }
-    let saved_gs = load_game(&save_name).unwrap();
-
-    saved_gs
 }
 
 fn load_game(filename: &str) -> io::Result<Gamestate> {
@@ -69,14 +69,12 @@ fn list_save_files() -> Vec<String> {
     let mut saves = Vec::new();
 
     if let Ok(entries) = fs::read_dir(SAVE_DIR) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                        saves.push(stem.to_string());
-                    }
-                }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            {
+                saves.push(stem.to_string());
             }
         }
     }
@@ -1,35 +1,39 @@
-mod bot;
 mod draw;
-mod gameconfig;
-mod gamelogic;
-mod gamestate;
+mod gameloop;
 mod loadgame;
 mod manualconfig;
 mod parse;
 mod prints;
 mod savegame;
+mod solve;
 mod startup;
+mod thinking;
 mod twoplayer;
-mod types;
 mod usersetup;
 
 use crate::{
-    bot::bot_guess,
     draw::draw_board,
-    gamelogic::{LoopAction, handle_end_of_round, human_guess},
-    gamestate::RoundStatus,
-    prints::print_complexity_analysis,
+    gameloop::{LoopAction, handle_end_of_round, human_batch, human_guess, human_scores_bot_guess},
+    prints::{
+        print_bot_settings, print_complexity_analysis, print_constraint_analysis,
+        print_sampling_analysis, print_word_analysis,
+    },
+    solve::run_solve_assistant,
     startup::handle_startup,
+    thinking::{bot_batch, bot_guess, bot_proposal},
+    usersetup::{Setup, user_setup},
+};
+
+use mastermind::{
+    bot::{BotMode, Difficulty},
+    sampling::uses_sampling,
     types::GameMode,
-    usersetup::user_setup,
 };
 
 use std::env;
 
 // TODO:
-// OPTIONAL: Convert bot logic from HashSet to Index-To-Line Conversion / Base-N Counting.
 // OPTIONAL: fix the math in time_estimation.
-// OPTIONAL: expand number of colors to allow more colors.
 // OPTIONAL: use real graphics lib or convert to TUI graphics.
 
 fn main() {
@@ -38,43 +42,65 @@ fn main() {
     }
     println!("Mastermind is running!");
 
-    let startup_action = user_setup();
-    let (mut gamestate, mut bot) = handle_startup(startup_action);
-    match gamestate.game_mode {
-        GameMode::Practice | GameMode::SpectateBot | GameMode::PlayerVsBot => {
-            gamestate.target_line = gamestate.randomize_target_line();
+    let startup_action = match user_setup() {
+        Setup::Game(action) => action,
+        // The solve assistant helps with a game played elsewhere; no game starts
+        Setup::Solve(cfg) => {
+            run_solve_assistant(&cfg);
+            return;
         }
-        _ => {}
-    }
+    };
+    let mut engine = handle_startup(startup_action, None);
 
     // MAIN GAME LOOP
     'game_session: loop {
-        gamestate.is_bot_guessing_this_round = gamestate.is_it_bots_turn_to_guess();
-
         // --- INPUTS ---
-        if !gamestate.round_over {
-            if gamestate.is_bot_guessing_this_round {
-                bot_guess(&mut gamestate, &mut bot);
+        if !engine.gamestate.round_over {
+            if engine.gamestate.is_awaiting_batch() {
+                // Static round: every guess but the last is played up front
+                if engine.is_bots_turn() {
+                    bot_batch(&mut engine);
+                } else {
+                    human_batch(&mut engine);
+                }
+            } else if engine.is_human_scoring() {
+                // The human Maker keeps the code and scores the bot by hand
+                if let Some(guess) = bot_proposal(&mut engine) {
+                    human_scores_bot_guess(&mut engine, guess);
+                }
+            } else if engine.is_bots_turn() {
+                bot_guess(&mut engine);
             } else {
                 // Human is guessing (P1 in PvB, Practice, or TwoPlayer)
-                human_guess(&mut gamestate);
+                human_guess(&mut engine);
             }
         }
 
-        // --- UPDATE ROUND STATES ---
-        gamestate.update_round_status();
-        gamestate.round_over = (gamestate.round_status == RoundStatus::Win)
-            || (gamestate.round_status == RoundStatus::Loss);
-
         // --- DRAW BOARD ---
-        draw_board(&gamestate);
-        if gamestate.game_mode == GameMode::SpectateBot {
-            print_complexity_analysis(gamestate.pegs_in_a_line, gamestate.is_empty_allowed);
+        draw_board(&engine.gamestate);
+        if engine.gamestate.game_mode == GameMode::SpectateBot {
+            let pegs = engine.gamestate.pegs_in_a_line;
+            let palette = engine.gamestate.palette();
+            print_bot_settings(&engine.gamestate.bot_settings);
+            if let Some(dictionary) = &engine.gamestate.dictionary {
+                print_word_analysis(pegs, dictionary.len());
+            } else if uses_sampling(
+                &engine.gamestate.bot_settings,
+                &engine.gamestate.code_space(),
+            ) {
+                print_sampling_analysis(pegs, palette.len());
+            } else if engine.gamestate.bot_settings.mode == BotMode::Constraint
+                && engine.gamestate.bot_settings.difficulty == Difficulty::Full
+            {
+                print_constraint_analysis(pegs, palette.len());
+            } else {
+                print_complexity_analysis(pegs, palette.len());
+            }
         }
 
         // --- HANDLE ROUND END ---
-        if gamestate.round_over {
-            match handle_end_of_round(&mut gamestate, &mut bot) {
+        if engine.gamestate.round_over {
+            match handle_end_of_round(&mut engine) {
                 LoopAction::Continue => continue 'game_session,
                 LoopAction::Break => break 'game_session,
             }
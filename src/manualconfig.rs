@@ -5,12 +5,44 @@
 
     Public API:
     - get_manual_config: prompts the user for game mode, number of guesses,
-      number of pegs, and whether empty pegs are allowed, returning a fully
-      populated GameConfig.
-    - parse_game_mode: parses a string input into a GameMode enum if valid.
+      number of pegs, number of colors, whether empty pegs are allowed, whether
+      colors may repeat, the feedback rule, whether rounds are static, how many
+      answers a round may be lies, how a
+      human's guesses are checked against earlier clues, whether the bot's codes
+      are adversarial, whether the human scores the bot by hand and, when a bot
+      plays, the bot difficulty (Player vs Bot only), mode and heuristic,
+      returning a fully populated GameConfig. For the word variant it asks
+      for the word list and word length instead of the pegs, colors, empty
+      pegs and repeats.
+    - get_manual_solve_config: asks only for the code rules (pegs, colors,
+      empty pegs, repeats) and the feedback rule, for the solve assistant.
 
     Internal helpers / private items:
+    - ask_code_rules: the pegs, colors, empty pegs and repeats questions both
+      setups share. Repeats can only be ruled out when the pegs don't
+      outnumber the symbols.
+    - ask_feedback_rule: standard, black-pegs-only or positional feedback
+      (positional only for games of up to MAX_POSITIONAL_WIDTH pegs, and
+      never for the solve assistant or hand scoring).
+    - ask_word_list: the path of a word list and its words of the length
+      asked for, asked again until the list has words of that length; none to play with
+      colors.
+    - ask_word_length: the letters in a word for the word variant.
+    - ask_bot_settings: the bot questions below, shared by both variants.
     - ask_game_mode: repeatedly prompts the user until a valid game mode is selected.
+    - ask_static_mode: whether rounds are static (only asked with at least
+      two guesses, and human scoring isn't offered then).
+    - ask_max_lies: how many answers a round may be lies (0-3); skipped for
+      static rounds and games too large for the bot to track lies in.
+    - ask_guess_check: off, warnings or hard mode; skipped in Spectate Bot.
+    - ask_evil_maker: whether the bot's codes are adversarial (Practice and
+      Player vs Bot only).
+    - ask_human_scored: whether the human scores the bot's guesses by hand
+      instead of typing in their code (Player vs Bot only).
+    - ask_difficulty / ask_bot_mode / ask_heuristic: same for the bot options,
+      only asked when the game mode has a bot and the option matters at the
+      chosen difficulty. A word game's bot is only offered Classic and
+      Knuth.
 
     Notes:
     - Input is validated to ensure reasonable values (e.g., 1-255 for guesses/pegs).
@@ -18,9 +50,21 @@
     - Empty peg inclusion is confirmed via yes/no style prompts.
 */
 
-use crate::{gameconfig::GameConfig, types::GameMode};
+use mastermind::{
+    bot::{
+        BotMode, BotSettings, Difficulty, Heuristic, parse_bot_mode, parse_difficulty,
+        parse_heuristic, plays_words,
+    },
+    gameconfig::{
+        GameConfig, fits_lies, fits_without_repeats, is_valid_number_of_colors,
+        parse_feedback_rule, parse_game_mode, parse_max_lies,
+    },
+    types::{DEFAULT_COLORS, FeedbackRule, GameMode, MAX_COLORS, MAX_POSITIONAL_WIDTH},
+    words::Dictionary,
+};
 
 use std::io::{self, Write};
+use std::sync::Arc;
 
 pub fn get_manual_config() -> GameConfig {
     io::stdout().flush().unwrap();
@@ -40,6 +84,144 @@ pub fn get_manual_config() -> GameConfig {
         }
     };
 
+    let word_list = ask_word_list();
+    let (pegs_in_a_line, number_of_colors, is_empty_pegs_allowed, no_repeats) = match &word_list {
+        // The words are the codes, so only their length is asked
+        Some((_, dictionary)) => (
+            dictionary.length() as u8,
+            DEFAULT_COLORS as u8,
+            false,
+            false,
+        ),
+        None => ask_code_rules(),
+    };
+
+    let feedback_rule = ask_feedback_rule(pegs_in_a_line as usize <= MAX_POSITIONAL_WIDTH);
+
+    let static_mode = number_of_guesses >= 2 && ask_static_mode();
+
+    let max_lies = if !static_mode
+        && (word_list.is_some()
+            || fits_lies(
+                pegs_in_a_line,
+                number_of_colors,
+                is_empty_pegs_allowed,
+                no_repeats,
+            )) {
+        ask_max_lies()
+    } else {
+        0
+    };
+
+    let (warn_inconsistent, hard_mode) = if game_mode == GameMode::SpectateBot {
+        (false, false)
+    } else {
+        ask_guess_check()
+    };
+
+    let evil_maker =
+        matches!(game_mode, GameMode::Practice | GameMode::PlayerVsBot) && ask_evil_maker();
+
+    let human_scored = game_mode == GameMode::PlayerVsBot
+        && !static_mode
+        && feedback_rule != FeedbackRule::Positional
+        && ask_human_scored();
+
+    let bot_settings = ask_bot_settings(game_mode, word_list.is_some());
+
+    GameConfig {
+        game_mode,
+        number_of_guesses,
+        pegs_in_a_line,
+        number_of_colors,
+        is_empty_pegs_allowed,
+        bot_settings,
+        warn_inconsistent,
+        hard_mode,
+        evil_maker,
+        human_scored,
+        no_repeats,
+        static_mode,
+        feedback_rule,
+        max_lies,
+        word_list: word_list.as_ref().map(|(path, _)| path.clone()),
+        dictionary: word_list.map(|(_, dictionary)| dictionary),
+        ..Default::default()
+    }
+}
+
+// Only the code rules, for the solve assistant; everything else stays default.
+pub fn get_manual_solve_config() -> GameConfig {
+    io::stdout().flush().unwrap();
+
+    let (pegs_in_a_line, number_of_colors, is_empty_pegs_allowed, no_repeats) = ask_code_rules();
+    let feedback_rule = ask_feedback_rule(false);
+    GameConfig {
+        pegs_in_a_line,
+        number_of_colors,
+        is_empty_pegs_allowed,
+        no_repeats,
+        feedback_rule,
+        ..Default::default()
+    }
+}
+
+// The path and its words, once the list has words of the length asked for.
+fn ask_word_list() -> Option<(String, Arc<Dictionary>)> {
+    loop {
+        println!(
+            "Play with words from a word list? Enter its path, or press Enter to play with colors:"
+        );
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let path = input.trim();
+        if path.is_empty() {
+            return None;
+        }
+        let length = ask_word_length();
+        match Dictionary::load(path, length as usize) {
+            Ok(dictionary) => {
+                println!("Loaded {} words of {} letters.", dictionary.len(), length);
+                return Some((path.to_string(), Arc::new(dictionary)));
+            }
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+fn ask_word_length() -> u8 {
+    println!("How many letters in a word? (2-15, Jotto uses 5)");
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().parse::<u8>() {
+            Ok(n) if (2..=15).contains(&n) => return n,
+            _ => println!("Please enter a number from 2 to 15:"),
+        }
+    }
+}
+
+fn ask_bot_settings(game_mode: GameMode, is_word_game: bool) -> BotSettings {
+    let mut bot_settings = BotSettings::default();
+    if matches!(game_mode, GameMode::PlayerVsBot | GameMode::SpectateBot) {
+        if game_mode == GameMode::PlayerVsBot {
+            bot_settings.difficulty = ask_difficulty();
+        }
+        if bot_settings.difficulty == Difficulty::Full {
+            bot_settings.mode = ask_bot_mode(is_word_game);
+        }
+        if matches!(
+            bot_settings.difficulty,
+            Difficulty::Greedy | Difficulty::Full
+        ) {
+            bot_settings.heuristic = ask_heuristic();
+        }
+    }
+    bot_settings
+}
+
+// (pegs_in_a_line, number_of_colors, is_empty_pegs_allowed, no_repeats)
+fn ask_code_rules() -> (u8, u8, bool, bool) {
     println!("How many pegs in a line do you wish to play with? (Base rules is 4)");
     let pegs_in_a_line: u8 = loop {
         let mut input = String::new();
@@ -53,6 +235,19 @@ pub fn get_manual_config() -> GameConfig {
         }
     };
 
+    println!(
+        "How many colors do you wish to play with? (2-{}, Base rules is 6)",
+        MAX_COLORS
+    );
+    let number_of_colors: u8 = loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().parse::<u8>() {
+            Ok(n) if is_valid_number_of_colors(n) => break n,
+            _ => println!("Please enter a number from 2 to {}:", MAX_COLORS),
+        }
+    };
+
     println!("Do you wish to include empty pegs? (y/n)");
     let is_empty_pegs_allowed: bool = loop {
         let mut input = String::new();
@@ -66,44 +261,195 @@ pub fn get_manual_config() -> GameConfig {
         }
     };
 
-    GameConfig {
-        game_mode,
-        number_of_guesses,
+    println!("Forbid repeated colors in a code (Bulls and Cows)? (y/n)");
+    let no_repeats: bool = loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes"
+                if fits_without_repeats(
+                    pegs_in_a_line,
+                    number_of_colors,
+                    is_empty_pegs_allowed,
+                ) =>
+            {
+                break true;
+            }
+            "y" | "yes" => println!(
+                "{} pegs can't all differ with {} symbols. Please enter 'n':",
+                pegs_in_a_line,
+                number_of_colors as usize + is_empty_pegs_allowed as usize
+            ),
+            "n" | "no" => break false,
+            _ => println!("Please enter 'y' or 'n':"),
+        }
+    };
+
+    (
         pegs_in_a_line,
+        number_of_colors,
         is_empty_pegs_allowed,
+        no_repeats,
+    )
+}
+
+fn ask_game_mode() -> GameMode {
+    loop {
+        print!(
+            "What game mode do you wish to play? (P)ractice / (2)-Player / Player Vs (B)ot) / (S)pectate Bot: "
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+
+        if let Some(mode) = parse_game_mode(&input) {
+            return mode;
+        }
+
+        println!("Please enter '(P)ractice', '(2)-Player' or 'Player Vs (B)ot' (S)pectate Bot: ");
     }
 }
 
-pub fn parse_game_mode(value: &str) -> Option<GameMode> {
-    let mode = value.trim().to_lowercase();
+fn ask_feedback_rule(allow_positional: bool) -> FeedbackRule {
+    if allow_positional {
+        println!("Which feedback? (S)tandard / (B)lack pegs only / (P)ositional marks (easy)");
+    } else {
+        println!("Which feedback? (S)tandard / (B)lack pegs only");
+    }
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
 
-    match mode.as_str() {
-        "p" | "practice" => Some(GameMode::Practice),
+        match parse_feedback_rule(&input) {
+            Some(FeedbackRule::Positional) if !allow_positional => {}
+            Some(rule) => return rule,
+            None => {}
+        }
 
-        "2" | "pvp" | "2p" | "two" | "two_player" | "twoplayer" | "2-player" => {
-            Some(GameMode::TwoPlayer)
+        if allow_positional {
+            println!("Please enter '(S)tandard', '(B)lack pegs only' or '(P)ositional marks':");
+        } else {
+            println!("Please enter '(S)tandard' or '(B)lack pegs only':");
         }
+    }
+}
 
-        "b" | "pvb" | "vsbot" | "player_vs_bot" | "player-vs-bot" => Some(GameMode::PlayerVsBot),
-        "s" | "spectate" | "spectate_bot" => Some(GameMode::SpectateBot),
-        _ => None,
+fn ask_static_mode() -> bool {
+    println!("Play static rounds (a batch of guesses up front, then one final guess)? (y/n)");
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => println!("Please enter 'y' or 'n':"),
+        }
     }
 }
 
-fn ask_game_mode() -> GameMode {
+fn ask_max_lies() -> u8 {
+    println!("How many answers a round may the Code Maker lie about? (0-3, 0 for none)");
     loop {
-        print!(
-            "What game mode do you wish to play? (P)ractice / (2)-Player / Player Vs (B)ot) / (S)pectate Bot: "
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match parse_max_lies(&input) {
+            Some(lies) if lies <= 3 => return lies,
+            _ => println!("Please enter a number from 0 to 3:"),
+        }
+    }
+}
+
+fn ask_evil_maker() -> bool {
+    println!("Should the bot's codes be evil (never commit to a code)? (y/n)");
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => println!("Please enter 'y' or 'n':"),
+        }
+    }
+}
+
+fn ask_human_scored() -> bool {
+    println!("When the bot breaks your code, keep it secret and score its guesses yourself? (y/n)");
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => println!("Please enter 'y' or 'n':"),
+        }
+    }
+}
+
+// (warn_inconsistent, hard_mode)
+fn ask_guess_check() -> (bool, bool) {
+    println!("Check guesses against earlier clues? (O)ff / (W)arn / (H)ard mode");
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "o" | "off" | "n" | "no" => return (false, false),
+            "w" | "warn" => return (true, false),
+            "h" | "hard" => return (true, true),
+            _ => println!("Please enter '(O)ff', '(W)arn' or '(H)ard mode':"),
+        }
+    }
+}
+
+fn ask_difficulty() -> Difficulty {
+    println!("How strong should the bot be? (1) Random / (2) Consistent / (3) Greedy / (4) Full");
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+
+        if let Some(difficulty) = parse_difficulty(&input) {
+            return difficulty;
+        }
+
+        println!("Please enter '1' (Random), '2' (Consistent), '3' (Greedy) or '4' (Full):");
+    }
+}
+
+fn ask_bot_mode(is_word_game: bool) -> BotMode {
+    if is_word_game {
+        println!("Which bot do you want to play with? (C)lassic / (K)nuth");
+        println!("(Word games have no Optimal, Sampling or Constraint bot.)");
+    } else {
+        println!(
+            "Which bot do you want to play with? (C)lassic / (K)nuth / (O)ptimal / (S)ampling / Co(n)straint"
         );
-        io::stdout().flush().unwrap();
+    }
+    loop {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+
+        match parse_bot_mode(&input) {
+            Some(mode) if !is_word_game || plays_words(mode) => return mode,
+            _ if is_word_game => println!("Please enter '(C)lassic' or '(K)nuth':"),
+            _ => println!(
+                "Please enter '(C)lassic', '(K)nuth', '(O)ptimal', '(S)ampling' or 'Co(n)straint':"
+            ),
+        }
+    }
+}
 
+fn ask_heuristic() -> Heuristic {
+    println!(
+        "How should the bot rank its guesses? (M)inimax / (E)ntropy / E(x)pected size / Most (P)arts"
+    );
+    loop {
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
 
-        if let Some(mode) = parse_game_mode(&input) {
-            return mode;
+        if let Some(heuristic) = parse_heuristic(&input) {
+            return heuristic;
         }
 
-        println!("Please enter '(P)ractice', '(2)-Player' or 'Player Vs (B)ot' (S)pectate Bot: ");
+        println!("Please enter '(M)inimax', '(E)ntropy', 'E(x)pected size' or 'Most (P)arts':");
     }
 }
@@ -0,0 +1,311 @@
+/*
+    Optimal solver module.
+
+    Finds the strategy with the minimum expected number of guesses for small
+    rule sets (up to MAX_OPTIMAL_SPACE codes, e.g. 4 pegs x 6 colors) by an
+    exhaustive, memoised branch-and-bound search over every possible guess.
+
+    Public API:
+    - MAX_OPTIMAL_SPACE: the largest search space the solver accepts.
+    - OptimalSolver::new: creates a solver for a CodeSpace, or None if it is too large.
+    - OptimalSolver::best_guess: the optimal next guess for a set of candidates.
+    - OptimalSolver::best_guess_within: the same, stopping early when a
+      SearchControl says so.
+    - OptimalSolver::total_guesses: the minimum total number of guesses needed to
+      solve every candidate of a set (divide by the set size for the average).
+
+    Internal helpers (private):
+    - solve: branch-and-bound search with a budget, memoised per candidate set.
+    - size_lower_bounds: lower bound on the total guesses for a set of each size.
+
+    Notes:
+    - The known optimum for 4x6 is 5625 guesses over 1296 codes (4.3403 average).
+    - Guesses that split the candidates the same way are only searched once, and
+      guesses that don't split them at all are skipped.
+    - Guesses that are symmetric images of each other (see the symmetry module)
+      are only searched once; e.g. the opening is chosen among 1111, 1112, 1122,
+      1123 and 1234 on 4x6.
+    - Results are memoised and kept for the lifetime of the solver, so the
+      first guess of a game is slow and the rest of the game (and later
+      rounds) replay from the memo.
+    - A search cut short stores nothing it found after the stop, so the memo
+      only ever holds exact results.
+*/
+
+use crate::{
+    feedbacktable::FeedbackTable,
+    strategy::SearchControl,
+    symmetry::Symmetries,
+    types::{Code, CodeSpace, Feedback},
+};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::Arc;
+
+pub const MAX_OPTIMAL_SPACE: u64 = 1296;
+
+#[derive(Clone, Copy)]
+enum Memo {
+    Exact(u32, Code),
+    AtLeast(u32),
+}
+
+pub struct OptimalSolver {
+    space: CodeSpace,
+    size: u64,
+    table: Arc<FeedbackTable>,
+    lower_bounds: Vec<u32>,
+    memo: FxHashMap<Vec<Code>, Memo>,
+    // Set once the running search was told to stop.
+    is_stopped: bool,
+}
+
+// One step of the search: the candidates left and the symmetries that survive
+// the guesses that led to them.
+struct Node<'a> {
+    candidates: &'a [Code],
+    symmetries: &'a Symmetries,
+    control: &'a SearchControl,
+    is_root: bool,
+}
+
+impl OptimalSolver {
+    pub fn new(space: CodeSpace, table: Arc<FeedbackTable>) -> Option<Self> {
+        let size = space.size().filter(|size| *size <= MAX_OPTIMAL_SPACE)?;
+        let lower_bounds = size_lower_bounds(&space, size as usize);
+        Some(OptimalSolver {
+            space,
+            size,
+            table,
+            lower_bounds,
+            memo: FxHashMap::default(),
+            is_stopped: false,
+        })
+    }
+
+    // `symmetries` must be the ones left by the guesses that led to `candidates`
+    // (Symmetries::new before the first guess).
+    pub fn best_guess(&mut self, candidates: &[Code], symmetries: &Symmetries) -> Code {
+        self.best_guess_within(candidates, symmetries, &SearchControl::unlimited())
+            .expect("an unbounded search always finds a strategy")
+    }
+
+    // If `control` says stop, the best guess whose search already finished
+    // is returned, or None when the search stopped before any did.
+    pub fn best_guess_within(
+        &mut self,
+        candidates: &[Code],
+        symmetries: &Symmetries,
+        control: &SearchControl,
+    ) -> Option<Code> {
+        self.solve_from(candidates, symmetries, control)
+            .map(|(_, guess)| guess)
+    }
+
+    pub fn total_guesses(&mut self, candidates: &[Code], symmetries: &Symmetries) -> u32 {
+        let (total, _) = self
+            .solve_from(candidates, symmetries, &SearchControl::unlimited())
+            .expect("an unbounded search always finds a strategy");
+        total
+    }
+
+    fn solve_from(
+        &mut self,
+        candidates: &[Code],
+        symmetries: &Symmetries,
+        control: &SearchControl,
+    ) -> Option<(u32, Code)> {
+        self.is_stopped = false;
+        let node = Node {
+            candidates,
+            symmetries,
+            control,
+            is_root: true,
+        };
+        self.solve(&node, u32::MAX)
+    }
+
+    // Minimum total guesses to solve every code in `candidates` (sorted), and the
+    // guess that achieves it, if that total is below `budget`.
+    fn solve(&mut self, node: &Node, budget: u32) -> Option<(u32, Code)> {
+        let candidates = node.candidates;
+        let n = candidates.len();
+        match n {
+            0 => return Some((0, 0)),
+            1 => return (budget > 1).then_some((1, candidates[0])),
+            2 => return (budget > 3).then_some((3, candidates[0])),
+            _ => {}
+        }
+        if self.lower_bounds[n] >= budget {
+            return None;
+        }
+        match self.memo.get(candidates) {
+            Some(Memo::Exact(total, guess)) => {
+                return (*total < budget).then_some((*total, *guess));
+            }
+            Some(Memo::AtLeast(bound)) if *bound >= budget => return None,
+            _ => {}
+        }
+
+        let width = self.space.width;
+        let win = Feedback {
+            correct_position: width as u8,
+            correct_color: 0,
+        }
+        .encode(width);
+
+        // Every useful guess with its lower bound, one guess per distinct split.
+        let guesses = node.symmetries.representatives(0..self.size);
+        let mut seen_splits = FxHashSet::default();
+        let mut options: Vec<(u32, bool, Code)> = Vec::new();
+        let mut feedbacks = vec![0; n];
+        let mut counts = vec![0usize; (width + 1) * (width + 1)];
+        let mut labels = vec![0u8; counts.len()];
+        for guess in guesses {
+            counts.iter_mut().for_each(|c| *c = 0);
+            for (i, candidate) in candidates.iter().enumerate() {
+                let encoded = self.table.get_encoded(*candidate, guess);
+                feedbacks[i] = encoded;
+                counts[encoded] += 1;
+            }
+            if counts.iter().enumerate().any(|(f, c)| f != win && *c == n) {
+                continue;
+            }
+            if !seen_splits.insert(split_signature(&feedbacks, win, &mut labels)) {
+                continue;
+            }
+            let bound: u32 = n as u32
+                + counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(f, _)| *f != win)
+                    .map(|(_, c)| self.lower_bounds[*c])
+                    .sum::<u32>();
+            let is_ruled_out = counts[win] == 0;
+            options.push((bound, is_ruled_out, guess));
+        }
+        options.sort_unstable();
+        if node.is_root {
+            node.control.set_total(options.len() as u64);
+        }
+
+        let mut best: Option<(u32, Code)> = None;
+        let mut best_total = budget;
+        for (bound, _, guess) in options {
+            if bound >= best_total {
+                break;
+            }
+            if node.control.should_stop() {
+                self.is_stopped = true;
+            }
+            if self.is_stopped {
+                break;
+            }
+
+            let mut classes: FxHashMap<usize, Vec<Code>> = FxHashMap::default();
+            for candidate in candidates {
+                let encoded = self.table.get_encoded(*candidate, guess);
+                if encoded != win {
+                    classes.entry(encoded).or_default().push(*candidate);
+                }
+            }
+            let mut classes: Vec<Vec<Code>> = classes.into_values().collect();
+            // Largest classes first, so hopeless guesses are abandoned early.
+            classes.sort_unstable_by_key(|class| std::cmp::Reverse(class.len()));
+
+            let symmetries = node.symmetries.after_guess(guess);
+            let mut total = n as u32;
+            let mut remaining_bound = bound - total;
+            let mut is_better = true;
+            for class in &classes {
+                remaining_bound -= self.lower_bounds[class.len()];
+                let class_budget = best_total - total - remaining_bound;
+                let child = Node {
+                    candidates: class,
+                    symmetries: &symmetries,
+                    control: node.control,
+                    is_root: false,
+                };
+                match self.solve(&child, class_budget) {
+                    Some((class_total, _)) if !self.is_stopped => total += class_total,
+                    _ => {
+                        is_better = false;
+                        break;
+                    }
+                }
+            }
+            if node.is_root {
+                node.control.advance(1);
+            }
+            if is_better && total < best_total {
+                best_total = total;
+                best = Some((total, guess));
+            }
+        }
+
+        // A cut-short search may have missed a better guess.
+        if self.is_stopped {
+            return best;
+        }
+        let entry = match best {
+            Some((total, guess)) => Memo::Exact(total, guess),
+            None => Memo::AtLeast(budget),
+        };
+        self.memo.insert(candidates.to_vec(), entry);
+        best
+    }
+}
+
+// Identifies how a guess splits the candidates, ignoring which feedback value
+// names each part (but keeping the winning part apart). `labels` is scratch
+// space with one slot per encoded feedback. The labels themselves are the
+// signature, so two different splits never count as one.
+fn split_signature(feedbacks: &[usize], win: usize, labels: &mut [u8]) -> Box<[u8]> {
+    labels.iter_mut().for_each(|label| *label = 0);
+    let mut next_label = 1;
+    feedbacks
+        .iter()
+        .map(|feedback| {
+            if *feedback == win {
+                u8::MAX
+            } else {
+                if labels[*feedback] == 0 {
+                    labels[*feedback] = next_label;
+                    next_label += 1;
+                }
+                labels[*feedback]
+            }
+        })
+        .collect()
+}
+
+// lower_bounds[n]: fewest total guesses any strategy could need for n codes.
+// A guess costs one guess per code and at best wins one code outright and
+// splits the rest evenly over the other feedback values.
+fn size_lower_bounds(space: &CodeSpace, max_size: usize) -> Vec<u32> {
+    let width = space.width;
+    // Feedback values other than "all correct" (n black + 1 white can't happen).
+    let parts = ((width + 1) * (width + 2) / 2).saturating_sub(2).max(1);
+
+    let mut bounds = vec![0u32; max_size + 1];
+    for n in 1..=max_size {
+        let even_split = |codes: usize| -> u32 {
+            let base = codes / parts;
+            let extra = codes % parts;
+            let larger_parts = if extra > 0 {
+                extra as u32 * bounds[base + 1]
+            } else {
+                0
+            };
+            larger_parts + (parts - extra) as u32 * bounds[base]
+        };
+        let with_win = n as u32 + even_split(n - 1);
+        let without_win = if n > 1 {
+            n as u32 + even_split(n)
+        } else {
+            u32::MAX
+        };
+        bounds[n] = with_win.min(without_win);
+    }
+    bounds
+}
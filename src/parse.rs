@@ -8,24 +8,46 @@
     - continue_playing: asks the user if they want to continue playing, quit, or save,
       returns true/false for continuation.
     - get_validated_line_input: reads a line of user input, validates color guesses
-      according to gamestate, and returns a populated Line struct.
+      against the palette in play, and returns a populated Line struct.
+    - get_guess_input: the same for the Code Breaker's guess, but also accepts
+      hint commands ("hint count", "hint suggest", "hint reveal").
+    - get_batch_input: the guesses of a static round's batch, one per line.
+    - parse_colors: turns a list of typed color names into a Line for a palette.
+    - get_feedback_input: reads the black and white counts a human Code Maker
+      gives for the bot's guess (e.g. "2 1" or "2b 1w"), or just the black
+      count under the black-pegs-only rule.
+    - get_guess_number_to_fix: asks which answer was scored wrong.
+    - ask_is_lie: whether the answer just given is one of the Maker's lies.
+
+    Public types:
+    - GuessInput: a guess line or a requested hint.
 
     Internal helpers / private items:
     - hide_line: conditionally clears the screen if input should be hidden.
+    - parse_line: a typed guess checked against the palette, width and the
+      no-repeats rule; in a word game, a typed word from the word list.
     - parse_guess: converts a string input into a Color enum, supports
       abbreviations and "empty" if allowed.
 
     Notes:
     - Validates the exact number of pegs for guesses.
-    - Supports flexible input for colors (full names or single-letter abbreviations).
+    - Supports flexible input for colors (full names, single-letter abbreviations,
+      or digits 0-9 for the colors in palette order, so "1 2 3 4" or "1234").
+    - With no repeated colors, a guess using a color twice is asked again.
     - Hides input for TwoPlayer mode to prevent cheating.
+    - "hint" on its own lists the hint commands instead of being read as a guess.
+    - Feedback counts are only checked for their format here; the engine
+      decides whether they are possible.
     - Loop continues until valid input is provided.
 */
 
-use crate::{
+use crate::savegame::handle_save_from_autosave;
+
+use mastermind::{
+    gamelogic::find_repeated_color,
     gamestate::Gamestate,
-    savegame::handle_save_from_autosave,
-    types::{Color, GameMode, Line},
+    hints::{HintKind, hint_command, parse_hint_kind},
+    types::{Color, Feedback, FeedbackRule, GameMode, Line, MAX_COLORS},
 };
 
 use crossterm::{
@@ -71,12 +93,24 @@ fn hide_line(need_to_hide_line: bool) {
     }
 }
 
+pub enum GuessInput {
+    Guess(Line),
+    Hint(HintKind),
+}
+
 pub fn get_validated_line_input(gamestate: &Gamestate) -> Line {
-    let need_to_hide_line = if gamestate.game_mode == GameMode::TwoPlayer {
-        true
-    } else {
-        false
-    };
+    match read_line_input(gamestate, false) {
+        GuessInput::Guess(line) => line,
+        GuessInput::Hint(_) => unreachable!("hints are only read for guesses"),
+    }
+}
+
+pub fn get_guess_input(gamestate: &Gamestate) -> GuessInput {
+    read_line_input(gamestate, true)
+}
+
+fn read_line_input(gamestate: &Gamestate, allow_hints: bool) -> GuessInput {
+    let need_to_hide_line = gamestate.game_mode == GameMode::TwoPlayer;
     loop {
         io::stdout().flush().unwrap();
 
@@ -84,35 +118,168 @@ pub fn get_validated_line_input(gamestate: &Gamestate) -> Line {
         io::stdin().read_line(&mut guess_input).unwrap();
         let guess = guess_input.trim();
 
-        let colors: Vec<&str> = guess.split_whitespace().collect();
-        let mut line = Line::empty(gamestate.pegs_in_a_line);
+        if allow_hints && let Some(command) = hint_command(gamestate, guess) {
+            match parse_hint_kind(command) {
+                Some(kind) => return GuessInput::Hint(kind),
+                None => {
+                    println!("Hints: 'hint count' (codes left), 'hint suggest' (bot's guess),");
+                    println!("'hint reveal' (one peg of the code). Each hint costs points.");
+                    continue;
+                }
+            }
+        }
+
+        let line = parse_line(gamestate, guess);
+        hide_line(need_to_hide_line);
+        match line {
+            Ok(line) => return GuessInput::Guess(line),
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+// One guess per line until an empty line or `max` guesses; at least one.
+pub fn get_batch_input(gamestate: &Gamestate, max: usize) -> Vec<Line> {
+    let need_to_hide_line = gamestate.game_mode == GameMode::TwoPlayer;
+    let mut batch = Vec::new();
+    while batch.len() < max {
+        print!("Guess {}: ", batch.len() + 1);
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        if input.is_empty() {
+            if batch.is_empty() {
+                println!("The batch needs at least one guess.");
+                continue;
+            }
+            break;
+        }
+
+        let line = parse_line(gamestate, input);
+        hide_line(need_to_hide_line);
+        match line {
+            Ok(line) => batch.push(line),
+            Err(message) => println!("{}", message),
+        }
+    }
+    batch
+}
+
+// A typed guess for the game's rules; the error is the message to show.
+fn parse_line(gamestate: &Gamestate, input: &str) -> Result<Line, String> {
+    let line = match &gamestate.dictionary {
+        Some(dictionary) => {
+            let word = dictionary.find(input).map_err(|error| error.to_string())?;
+            dictionary.line(word)
+        }
+        None => {
+            let colors: Vec<&str> = input.split_whitespace().collect();
+            parse_colors(&colors, &gamestate.palette(), gamestate.pegs_in_a_line)?
+        }
+    };
+    match find_repeated_color(&line).filter(|_| gamestate.no_repeats) {
+        Some(color) => Err(format!(
+            "{:?} is used more than once; colors can't repeat.",
+            color
+        )),
+        None => Ok(line),
+    }
+}
+
+// Reads one color per word, or a single word of digits ("1234"); the error
+// is the message to show the player.
+pub fn parse_colors(colors: &[&str], palette: &[Color], width: usize) -> Result<Line, String> {
+    if let [word] = colors
+        && width > 1
+        && word.len() == width
+        && word.chars().all(|c| c.is_ascii_digit())
+    {
+        let digits: Vec<String> = word.chars().map(String::from).collect();
+        let digits: Vec<&str> = digits.iter().map(String::as_str).collect();
+        return parse_colors(&digits, palette, width);
+    }
+    if colors.len() != width {
+        return Err(format!("You must enter exactly {} colors.", width));
+    }
 
-        if colors.len() != gamestate.pegs_in_a_line {
-            hide_line(need_to_hide_line);
-            println!(
-                "You must enter exactly {} colors.",
-                gamestate.pegs_in_a_line
-            );
-            continue;
+    let mut line = Line::empty(width);
+    for (i, c) in colors.iter().enumerate() {
+        let color = parse_guess(c);
+        // Prevent setting an Invalid color unless "empty" was typed
+        let is_typed_empty = matches!(c.to_lowercase().as_str(), "e" | "empty");
+        if (color == Color::Empty && !is_typed_empty) || !palette.contains(&color) {
+            return Err(format!("Invalid color: '{}'", c));
         }
+        line.pegs[i].color = color;
+    }
+    Ok(line)
+}
 
-        let mut valid = true;
-        for (i, c) in colors.iter().enumerate() {
-            let color = parse_guess(c);
-            // Prevent setting an Invalid color unless "empty" was typed
-            if color == Color::Empty && (c.to_lowercase() != "empty" || !gamestate.is_empty_allowed)
-            {
-                hide_line(need_to_hide_line);
-                println!("Invalid color: '{}'", c);
-                valid = false;
-                break;
+pub fn get_feedback_input(rule: FeedbackRule) -> Feedback {
+    loop {
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+
+        // Any non-digits separate the two counts: "2 1", "2,1", "2b 1w". A
+        // count too large for a u8 makes the whole answer unreadable.
+        let counts: Option<Vec<u8>> = input
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().ok())
+            .collect();
+        match (rule, counts.as_deref()) {
+            (FeedbackRule::BlackOnly, Some([black])) => {
+                return Feedback {
+                    correct_position: *black,
+                    correct_color: 0,
+                };
+            }
+            (FeedbackRule::BlackOnly, _) => print!("Please enter the number of black pegs: "),
+            (_, Some([black, white])) => {
+                return Feedback {
+                    correct_position: *black,
+                    correct_color: *white,
+                };
             }
-            line.pegs[i].color = color;
+            _ => print!("Please enter two numbers, black then white (e.g. '2 1'): "),
+        }
+    }
+}
+
+// Enter (or the current guess's number) means the answer just given.
+pub fn get_guess_number_to_fix(current: usize) -> usize {
+    print!(
+        "Enter the number of the guess you scored wrong (1-{}), or press Enter to score guess {} again: ",
+        current, current
+    );
+    loop {
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        if input.is_empty() {
+            return current;
+        }
+        match input.parse::<usize>() {
+            Ok(number) if (1..=current).contains(&number) => return number,
+            _ => print!("Please enter a number from 1 to {}: ", current),
         }
+    }
+}
 
-        if valid {
-            hide_line(need_to_hide_line);
-            return line;
+pub fn ask_is_lie(lies_left: usize) -> bool {
+    print!("Is that answer a lie? ({} left) (y/n): ", lies_left);
+    loop {
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" | "" => return false,
+            _ => print!("Please enter 'y' or 'n': "),
         }
     }
 }
@@ -126,6 +293,14 @@ fn parse_guess(guess: &str) -> Color {
         "g" | "green" => Color::Green,
         "u" | "blue" => Color::Blue,
         "y" | "yellow" => Color::Yellow,
+        "o" | "orange" => Color::Orange,
+        "p" | "purple" => Color::Purple,
+        "c" | "cyan" => Color::Cyan,
+        "m" | "magenta" => Color::Magenta,
+        // Digits name the colors in palette order, for Bulls and Cows.
+        digit if digit.len() == 1 && digit.as_bytes()[0].is_ascii_digit() => {
+            Color::palette(MAX_COLORS, false)[(digit.as_bytes()[0] - b'0') as usize]
+        }
         _ => Color::Empty, // Invalid input
     }
 }
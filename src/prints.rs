@@ -7,6 +7,19 @@
     - print_win_or_loss: prints whether the target was solved and displays the target line.
     - print_round_summary: prints a summary of the round including winner/loser, scores,
       and bonus points if any.
+    - print_complexity_analysis: prints the bot's search space size and time estimate.
+    - print_sampling_analysis: the same view when the sampling bot plays instead.
+    - print_constraint_analysis: the same view for the constraint bot.
+    - print_word_analysis: the same view for a word game.
+    - print_bot_settings: prints the active bot mode, heuristic and difficulty (Spectate Bot view),
+      and the thinking time limit and thread cap when set.
+    - print_hint: prints the answer to a hint and how many hints were used.
+    - print_contradiction_warning: warns that a guess can't be the code.
+    - print_solve_board / print_remaining / print_evaluation / print_recommendation:
+      the solve assistant's board, remaining codes, guess ratings and advice.
+    - print_bot_guess: shows the bot's guess to a Code Maker scoring it by hand.
+    - print_thinking_progress: rewrites the current line with the bot's search
+      progress, elapsed time and estimated time left.
 
     Internal helpers / private items:
     - print_target_line: prints a Line struct’s peg colors in a human-readable format.
@@ -20,26 +33,123 @@
     - Round summaries include clear separation markers for readability.
 */
 
-use crate::{
-    gamelogic::RoundResult,
+use mastermind::{
+    assistant::{GuessEvaluation, Remaining},
+    bot::BotSettings,
+    gamelogic::{Contradiction, RoundResult},
     gamestate::Gamestate,
-    types::{GameMode, Line},
+    hints::Hint,
+    strategy::SearchControl,
+    types::{Feedback, GameMode, Line},
 };
 
+use std::io::{Write, stdout};
+
 fn print_target_line(target: &Line) {
     for peg in target.pegs.iter() {
         print!("{:?} ", peg.color);
     }
-    print!("\n");
+    println!();
+}
+
+pub fn print_solve_board(answers: &[(Line, Feedback)]) {
+    if answers.is_empty() {
+        println!("No guesses entered yet.");
+    }
+    for (i, (guess, feedback)) in answers.iter().enumerate() {
+        println!(
+            "{:>3}. {} -> {} black, {} white",
+            i + 1,
+            guess,
+            feedback.correct_position,
+            feedback.correct_color
+        );
+    }
+}
+
+pub fn print_remaining(remaining: &Remaining) {
+    match remaining {
+        Remaining::Codes(codes) => {
+            println!("{} codes still fit:", codes.len());
+            for code in codes {
+                println!("  {}", code);
+            }
+        }
+        Remaining::Count(count) => {
+            println!("{} codes still fit.", format_number(*count as u128))
+        }
+        Remaining::TooMany => println!("Too many codes still fit to count."),
+    }
+}
+
+pub fn print_evaluation(evaluation: &GuessEvaluation) {
+    if evaluation.is_consistent {
+        println!("This guess fits every answer, so it could be the code.");
+    } else {
+        println!("This guess can't be the code, but it can still narrow things down.");
+    }
+    println!(
+        "Of {} codes left: worst case {}, on average {:.2} left afterwards, heuristic cost {}.",
+        format_number(evaluation.remaining as u128),
+        format_number(evaluation.worst_case as u128),
+        evaluation.expected_size,
+        evaluation.cost
+    );
+    for (feedback, count) in &evaluation.parts {
+        println!(
+            "  {} black, {} white: {} codes",
+            feedback.correct_position, feedback.correct_color, count
+        );
+    }
+}
+
+pub fn print_recommendation(guess: &Line) {
+    print!("Recommended guess: ");
+    print_target_line(guess);
+}
+
+pub fn print_bot_guess(guess_number: usize, guess: &Line) {
+    print!("Guess {}: the bot guesses ", guess_number);
+    print_target_line(guess);
+}
+
+pub fn print_hint(hint: Option<&Hint>, hints_used: u8) {
+    match hint {
+        Some(Hint::Count(Some(count))) => {
+            println!(
+                "{} codes still fit the board.",
+                format_number(*count as u128)
+            )
+        }
+        Some(Hint::Count(None)) => println!("Too many codes still fit the board to count."),
+        Some(Hint::Suggestion(line)) => {
+            print!("The bot would guess: ");
+            print_target_line(line);
+        }
+        Some(Hint::RevealPeg { position, color }) => {
+            println!("Peg {} of the code is {:?}.", position + 1, color)
+        }
+        None => println!("No hint available (every peg is already revealed)."),
+    }
+    println!("Hints used this round: {}", hints_used);
+}
+
+pub fn print_contradiction_warning(contradiction: &Contradiction) {
+    println!("Warning: that can't be the code. {}", contradiction);
 }
 
 pub fn print_win_or_loss(gamestate: &Gamestate, round_result: &RoundResult) {
-    if round_result.is_win == true {
+    if round_result.is_win {
         print!(
             "Code solved with {} out of {} guesses!\nThe target was: ",
             round_result.guesses_used, gamestate.round_length
         );
         print_target_line(&gamestate.target_line);
+    } else if gamestate.is_human_scored_round() {
+        println!(
+            "Target not found in {} guesses. The code stays with the Code Maker.",
+            gamestate.round_length
+        );
     } else {
         print!(
             "Target not found in {} guesses.\nThe target was: ",
@@ -48,6 +158,9 @@ pub fn print_win_or_loss(gamestate: &Gamestate, round_result: &RoundResult) {
         // Just wanna test this print for now. Nevermind it's perfection.
         print_target_line(&gamestate.target_line);
     }
+    if round_result.hints_used > 0 {
+        println!("Hints used: {}", round_result.hints_used);
+    }
 }
 
 fn get_player_strings(gamestate: &Gamestate) -> (&'static str, &'static str) {
@@ -89,6 +202,11 @@ pub fn print_round_summary(gamestate: &Gamestate, result: &RoundResult) {
     } else {
         "".to_string()
     };
+    let hint_str = if result.hint_penalty != 0 {
+        format!(" + {} for hints", result.hint_penalty)
+    } else {
+        "".to_string()
+    };
     let p2_string = if gamestate.game_mode == GameMode::PlayerVsBot {
         "BOT"
     } else {
@@ -101,8 +219,8 @@ pub fn print_round_summary(gamestate: &Gamestate, result: &RoundResult) {
         breaker, win_or_loss_str, result.guesses_used
     );
     println!(
-        "{} (Code Maker) gains {}{} points.",
-        maker, result.guesses_used, bonus_str
+        "{} (Code Maker) gains {}{}{} points.",
+        maker, result.guesses_used, bonus_str, hint_str
     );
     println!(
         "--- SCOREBOARD: P1: {} | {}: {}",
@@ -110,18 +228,16 @@ pub fn print_round_summary(gamestate: &Gamestate, result: &RoundResult) {
     );
 }
 
-pub fn print_complexity_analysis(pegs: usize, allow_empty: bool) {
-    let colors: u128 = if allow_empty { 7 } else { 6 };
+pub fn print_complexity_analysis(pegs: usize, colors: usize) {
+    let colors = colors as u128;
     let width = pegs as u32;
 
     // Calculate Search Space (N)
-    let total_combinations = colors.pow(width);
+    let total_combinations = colors.checked_pow(width).unwrap_or(u128::MAX);
 
     // Calculate Minimax Complexity (N^2)
     // Since we compare every possible solution against every other solution
-    let minimax_checks = total_combinations
-        .checked_mul(total_combinations)
-        .unwrap_or(u128::MAX);
+    let minimax_checks = total_combinations.saturating_mul(total_combinations);
 
     // Estimate CPU to 20 million "match checks" per second
     let ops_per_sec = 20_000_000_u128;
@@ -165,17 +281,95 @@ pub fn print_complexity_analysis(pegs: usize, allow_empty: bool) {
     println!("----------------------------------------------------\n");
 }
 
+pub fn print_sampling_analysis(pegs: usize, colors: usize) {
+    println!("\n---   SAMPLING BOT   ---");
+    println!("{:<20} : {}", "Pegs", pegs);
+    println!("{:<20} : {}", "Colors", colors);
+    println!(
+        "{:<20} : {}^{} (too many to enumerate)",
+        "Total Candidates", colors, pegs
+    );
+    println!("The bot searches for codes that fit the feedback so far and guesses");
+    println!("from a small sample of them. Each turn takes a bounded amount of time;");
+    println!("if no fitting code turns up it plays the closest one it found.");
+    println!("----------------------------------------------------\n");
+}
+
+pub fn print_word_analysis(letters: usize, words: usize) {
+    println!("\n---   WORD GAME   ---");
+    println!("{:<20} : {}", "Letters", letters);
+    println!("{:<20} : {}", "Words", format_number(words as u128));
+    println!("The bot only guesses words from the list. A Knuth turn scores every");
+    println!("word against every word still in play.");
+    println!("----------------------------------------------------\n");
+}
+
+pub fn print_constraint_analysis(pegs: usize, colors: usize) {
+    println!("\n---   CONSTRAINT BOT   ---");
+    println!("{:<20} : {}", "Pegs", pegs);
+    println!("{:<20} : {}", "Colors", colors);
+    println!("The bot searches for a code that fits every clue so far, without");
+    println!("listing the candidates. Quick up to about 16 pegs with 8 colors;");
+    println!("(!) wider codes can take seconds to minutes per turn.");
+    println!("----------------------------------------------------\n");
+}
+
+pub fn print_bot_settings(settings: &BotSettings) {
+    println!("{:<20} : {}", "Bot Mode", settings.mode);
+    println!("{:<20} : {}", "Bot Heuristic", settings.heuristic);
+    println!("{:<20} : {}", "Bot Difficulty", settings.difficulty);
+    if let Some(limit) = settings.time_limit() {
+        println!(
+            "{:<20} : {:.1}s per guess",
+            "Bot Time Limit",
+            limit.as_secs_f64()
+        );
+    }
+    if let Some(threads) = settings.threads {
+        println!("{:<20} : {}", "Bot Threads", threads);
+    }
+}
+
+pub fn print_thinking_progress(control: &SearchControl) {
+    let (done, total) = control.progress();
+    let elapsed = control.elapsed();
+    let mut status = format!("Bot thinking... {:.1}s", elapsed.as_secs_f64());
+    if let Some(percent) = (done.min(total) * 100).checked_div(total) {
+        status += &format!(
+            " | {}% ({}/{})",
+            percent,
+            format_number(done as u128),
+            format_number(total as u128)
+        );
+    } else if done > 0 {
+        status += &format!(" | {} steps", format_number(done as u128));
+    }
+    // Never promise to take longer than the time limit allows.
+    let time_left = control
+        .time_limit()
+        .map(|limit| limit.saturating_sub(elapsed));
+    let eta = match (control.eta(), time_left) {
+        (Some(eta), Some(left)) => Some(eta.min(left)),
+        (eta, left) => eta.or(left),
+    };
+    if let Some(eta) = eta {
+        status += &format!(" | ~{:.0}s left", eta.as_secs_f64().ceil());
+    }
+    status += " | Esc: play best so far";
+    // Raw mode is on while the bot thinks, so go back to the line start by hand.
+    print!("\r{:<80}", status);
+    stdout().flush().ok();
+}
+
 // Makes big numbers readable
 fn format_number(n: u128) -> String {
     let s = n.to_string();
     let mut result = String::new();
-    let mut count = 0;
-    for c in s.chars().rev() {
+    for (count, c) in s.chars().rev().enumerate() {
         if count > 0 && count % 3 == 0 {
             result.push(',');
         }
         result.push(c);
-        count += 1;
     }
     result.chars().rev().collect()
 }
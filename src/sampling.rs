@@ -0,0 +1,455 @@
+/*
+    Sampling bot module.
+
+    Provides SamplingBot, a Code Breaker for rule sets whose search space is far
+    too large to enumerate (up to 255 pegs). It never builds the space: it looks
+    for codes consistent with the feedback so far by constraint-guided random
+    local search, and picks its guess from that sample.
+
+    Public API:
+    - MAX_ENUMERATED_SPACE: largest search space the enumerating Bot is used for.
+    - uses_sampling: whether the engine seats a SamplingBot for a rule set
+      (Sampling mode, a space too large for the enumerating modes, or a code
+      too wide for Constraint mode, without repeated colors or under another
+      feedback rule; below Full
+      difficulty, and with lies allowed, only the size counts).
+    - SamplingBot::new: creates a bot for a palette, peg count and BotSettings.
+    - SamplingBot::with_space: the same for a CodeSpace, e.g. one without
+      repeated colors.
+    - impl BreakerStrategy for SamplingBot.
+
+    Internal helpers (private):
+    - Clue: an earlier guess with its feedback, kept as palette indices
+      (shared with the constraint module, as are to_digits / to_line).
+    - allowed_colors: colors per position that the feedback hasn't ruled out.
+    - Search: a code under local search, with incremental match counts against
+      every clue so a one-peg change costs O(clues) instead of O(clues * pegs).
+    - sample_consistent: up to SAMPLE_SIZE distinct consistent codes.
+    - pick_guess: ranks the sample by how it splits itself, using the heuristic.
+
+    Notes:
+    - Every search has a fixed step budget. If no consistent code turns up the
+      bot plays the least inconsistent code it saw, so a huge game keeps moving
+      instead of hanging (the bot may simply run out of guesses).
+    - Under the black-pegs-only rule a clue's total of matches is unknown,
+      so it neither rules colors out nor counts toward a code's error.
+    - Under the positional rule the bot only uses the counts of each answer
+      (see BreakerStrategy::observe_flags).
+    - Searches also end early when the time limit passes or the player
+      cancels; the guess is then picked from whatever was found so far.
+    - Only the heuristic from BotSettings is used; the Sampling mode only
+      decides that this bot is seated even when the space could be enumerated.
+*/
+
+use crate::{
+    bot::{BotMode, BotSettings, Difficulty},
+    constraint::MAX_CONSTRAINT_WIDTH,
+    gamelogic::score_digits,
+    strategy::{BreakerStrategy, SearchControl},
+    types::{CodeSpace, Color, Feedback, FeedbackRule, Line},
+};
+
+use rand::{Rng, rng, seq::IndexedRandom};
+use rayon::prelude::*;
+
+pub const MAX_ENUMERATED_SPACE: u64 = 1 << 20;
+
+const SAMPLE_SIZE: usize = 32;
+const SEARCHES_PER_GUESS: usize = 2 * SAMPLE_SIZE;
+const START_TEMPERATURE: f64 = 1.5;
+const SWAP_CHANCE: f64 = 0.3;
+const STEPS_PER_STOP_CHECK: usize = 256;
+
+pub fn uses_sampling(settings: &BotSettings, space: &CodeSpace) -> bool {
+    let is_too_large = space
+        .count()
+        .is_none_or(|count| count > MAX_ENUMERATED_SPACE);
+    match settings.mode {
+        // Guesses must be words, which only the enumerating Bot knows.
+        _ if space.dictionary.is_some() => false,
+        // Below full strength the mode is ignored.
+        _ if settings.difficulty != Difficulty::Full => is_too_large,
+        // Only the enumerating Bot counts the lies each code needs.
+        _ if space.max_lies > 0 => is_too_large,
+        BotMode::Sampling => true,
+        // The constraint search knows no rule against repeated colors, and
+        // needs both counts of every answer.
+        BotMode::Constraint => {
+            space.no_repeats
+                || space.feedback_rule != FeedbackRule::Standard
+                || space.width > MAX_CONSTRAINT_WIDTH
+        }
+        _ => is_too_large,
+    }
+}
+
+// Also used by the constraint module.
+pub(crate) struct Clue {
+    pub(crate) guess: Vec<u8>,
+    pub(crate) guess_counts: Vec<u16>,
+    pub(crate) correct_position: u16,
+    pub(crate) total_matches: u16,
+    // False when the rule hides the white count, so the total is unknown.
+    pub(crate) is_total_known: bool,
+}
+
+impl Clue {
+    pub(crate) fn new(guess: Vec<u8>, feedback: Feedback, base: usize) -> Self {
+        let mut guess_counts = vec![0; base];
+        for color in &guess {
+            guess_counts[*color as usize] += 1;
+        }
+        Clue {
+            guess,
+            guess_counts,
+            correct_position: feedback.correct_position as u16,
+            total_matches: feedback.correct_position as u16 + feedback.correct_color as u16,
+            is_total_known: true,
+        }
+    }
+}
+
+pub struct SamplingBot {
+    settings: BotSettings,
+    palette: Vec<Color>,
+    width: usize,
+    no_repeats: bool,
+    feedback_rule: FeedbackRule,
+    clues: Vec<Clue>,
+}
+
+impl SamplingBot {
+    pub fn new(palette: Vec<Color>, no_of_pegs: usize, settings: BotSettings) -> Self {
+        Self::with_space(CodeSpace::new(palette, no_of_pegs), settings)
+    }
+
+    pub fn with_space(space: CodeSpace, settings: BotSettings) -> Self {
+        SamplingBot {
+            settings,
+            palette: space.palette,
+            width: space.width,
+            no_repeats: space.no_repeats,
+            feedback_rule: space.feedback_rule,
+            clues: Vec::new(),
+        }
+    }
+
+    // Colors each position may still hold: a clue with no exact matches rules
+    // out its color at each position, a clue with no matches at all rules out
+    // its colors everywhere.
+    fn allowed_colors(&self) -> Vec<Vec<u8>> {
+        let base = self.palette.len();
+        let mut is_allowed = vec![vec![true; base]; self.width];
+        for clue in &self.clues {
+            for (position, color) in clue.guess.iter().enumerate() {
+                if clue.is_total_known && clue.total_matches == 0 {
+                    is_allowed
+                        .iter_mut()
+                        .for_each(|allowed| allowed[*color as usize] = false);
+                } else if clue.correct_position == 0 {
+                    is_allowed[position][*color as usize] = false;
+                }
+            }
+        }
+        is_allowed
+            .into_iter()
+            .map(|allowed| {
+                let colors: Vec<u8> = (0..base as u8).filter(|c| allowed[*c as usize]).collect();
+                // Contradictory feedback (e.g. a human mistake): don't constrain.
+                if colors.is_empty() {
+                    (0..base as u8).collect()
+                } else {
+                    colors
+                }
+            })
+            .collect()
+    }
+
+    // Runs SEARCHES_PER_GUESS local searches in parallel. Returns the distinct
+    // consistent codes found (at most SAMPLE_SIZE), or the least inconsistent
+    // code seen if there were none.
+    fn sample_consistent(&self, control: &SearchControl) -> Result<Vec<Vec<u8>>, Vec<u8>> {
+        let allowed = self.allowed_colors();
+        let base = self.palette.len();
+        let steps = 2_000 + 1_000 * self.width;
+        control.set_total(SEARCHES_PER_GUESS as u64);
+        let results: Vec<(u32, Vec<u8>)> = (0..SEARCHES_PER_GUESS)
+            .into_par_iter()
+            .map(|_| {
+                let result =
+                    Search::run(&self.clues, &allowed, base, self.no_repeats, steps, control);
+                control.advance(1);
+                result
+            })
+            .collect();
+
+        let mut sample: Vec<Vec<u8>> = Vec::new();
+        for (error, code) in &results {
+            if *error == 0 && !sample.contains(code) && sample.len() < SAMPLE_SIZE {
+                sample.push(code.clone());
+            }
+        }
+        if sample.is_empty() {
+            let (_, least_inconsistent) = results
+                .into_iter()
+                .min_by_key(|(error, _)| *error)
+                .expect("at least one search runs");
+            return Err(least_inconsistent);
+        }
+        Ok(sample)
+    }
+
+    // The code in `sample` whose feedback against the rest of the sample gives
+    // the lowest heuristic cost.
+    fn pick_guess(&self, sample: Vec<Vec<u8>>) -> Vec<u8> {
+        let width = self.width;
+        let best = (0..sample.len())
+            .into_par_iter()
+            .min_by_key(|i| {
+                let mut encoded: Vec<usize> = sample
+                    .iter()
+                    .map(|target| {
+                        self.feedback_rule
+                            .apply(score_digits(target, &sample[*i]))
+                            .encode(width)
+                    })
+                    .collect();
+                encoded.sort_unstable();
+                let mut counts: Vec<usize> = encoded
+                    .chunk_by(|a, b| a == b)
+                    .map(|part| part.len())
+                    .collect();
+                // Same length for every guess so MostParts can compare them.
+                counts.resize(sample.len(), 0);
+                (self.settings.heuristic.cost(&counts), *i)
+            })
+            .expect("sample is never empty");
+        sample[best].clone()
+    }
+}
+
+impl BreakerStrategy for SamplingBot {
+    fn next_guess(&mut self) -> Line {
+        self.next_guess_within(&SearchControl::unlimited())
+    }
+
+    fn next_guess_within(&mut self, control: &SearchControl) -> Line {
+        let guess = match self.sample_consistent(control) {
+            Ok(sample) => self.pick_guess(sample),
+            Err(least_inconsistent) => least_inconsistent,
+        };
+        to_line(&self.palette, &guess)
+    }
+
+    fn observe_feedback(&mut self, guess: &Line, feedback: Feedback) {
+        let guess = to_digits(&self.palette, guess);
+        let mut clue = Clue::new(guess, feedback, self.palette.len());
+        clue.is_total_known = self.feedback_rule != FeedbackRule::BlackOnly;
+        self.clues.push(clue);
+    }
+
+    fn reset_for_round(&mut self) {
+        self.clues.clear();
+    }
+}
+
+pub(crate) fn to_digits(palette: &[Color], line: &Line) -> Vec<u8> {
+    line.pegs
+        .iter()
+        .map(|peg| {
+            palette
+                .iter()
+                .position(|color| *color == peg.color)
+                .expect("guess uses a color outside the bot's palette") as u8
+        })
+        .collect()
+}
+
+pub(crate) fn to_line(palette: &[Color], digits: &[u8]) -> Line {
+    Line::new(digits.iter().map(|d| palette[*d as usize]).collect())
+}
+
+// A code being improved one peg at a time. For every clue it keeps the exact
+// and total matches the code would get, and `error` sums how far those are
+// from the clue's feedback (0 means the code is consistent).
+struct Search<'a> {
+    clues: &'a [Clue],
+    code: Vec<u8>,
+    counts: Vec<u16>,
+    correct_positions: Vec<u16>,
+    total_matches: Vec<u16>,
+    error: u32,
+}
+
+impl<'a> Search<'a> {
+    // Simulated annealing from a random allowed code: moves recolor one peg or
+    // swap two, and worse moves are accepted less and less often as the search
+    // cools. Without repeats, recoloring a peg to a color another peg holds
+    // swaps the two instead. Returns the lowest error reached and the code
+    // that reached it, early if `control` says stop.
+    fn run(
+        clues: &'a [Clue],
+        allowed: &[Vec<u8>],
+        base: usize,
+        no_repeats: bool,
+        steps: usize,
+        control: &SearchControl,
+    ) -> (u32, Vec<u8>) {
+        let mut rng = rng();
+        let width = allowed.len();
+        let code = if no_repeats {
+            random_distinct_code(allowed, base, &mut rng)
+        } else {
+            allowed
+                .iter()
+                .map(|colors| colors[rng.random_range(0..colors.len())])
+                .collect()
+        };
+        let mut search = Search::new(clues, code, base);
+        let mut best = (search.error, search.code.clone());
+
+        for step in 0..steps {
+            if search.error == 0
+                || (step.is_multiple_of(STEPS_PER_STOP_CHECK) && control.should_stop())
+            {
+                break;
+            }
+            let temperature = START_TEMPERATURE * (1.0 - step as f64 / steps as f64) + 0.05;
+            let accepts = |rng: &mut rand::rngs::ThreadRng, old: u32, new: u32| {
+                new <= old || rng.random::<f64>() < (-((new - old) as f64) / temperature).exp()
+            };
+
+            let first = rng.random_range(0..width);
+            let mut swap_with = None;
+            if rng.random_bool(SWAP_CHANCE) {
+                swap_with = Some(rng.random_range(0..width));
+            } else {
+                let colors = &allowed[first];
+                let color = colors[rng.random_range(0..colors.len())];
+                if color == search.code[first] {
+                    continue;
+                }
+                let holder = search.code.iter().position(|c| *c == color);
+                if no_repeats && holder.is_some() {
+                    swap_with = holder;
+                } else {
+                    let error = search.error_after(first, color);
+                    if accepts(&mut rng, search.error, error) {
+                        search.set(first, color, error);
+                    }
+                }
+            }
+            if let Some(second) = swap_with {
+                let (a, b) = (search.code[first], search.code[second]);
+                if a == b || !allowed[first].contains(&b) || !allowed[second].contains(&a) {
+                    continue;
+                }
+                let old = search.error;
+                search.set(first, b, search.error_after(first, b));
+                search.set(second, a, search.error_after(second, a));
+                if !accepts(&mut rng, old, search.error) {
+                    search.set(second, b, search.error_after(second, b));
+                    search.set(first, a, old);
+                }
+            }
+            if search.error < best.0 {
+                best = (search.error, search.code.clone());
+            }
+        }
+        best
+    }
+
+    fn new(clues: &'a [Clue], code: Vec<u8>, base: usize) -> Self {
+        let mut counts = vec![0u16; base];
+        for color in &code {
+            counts[*color as usize] += 1;
+        }
+        let mut search = Search {
+            clues,
+            correct_positions: Vec::with_capacity(clues.len()),
+            total_matches: Vec::with_capacity(clues.len()),
+            code,
+            counts,
+            error: 0,
+        };
+        for clue in clues {
+            let feedback = score_digits(&search.code, &clue.guess);
+            let correct_position = feedback.correct_position as u16;
+            let total = correct_position + feedback.correct_color as u16;
+            search.error += clue_error(clue, correct_position, total);
+            search.correct_positions.push(correct_position);
+            search.total_matches.push(total);
+        }
+        search
+    }
+
+    // Change in exact and total matches against `clue` if the peg at `position`
+    // became `color`.
+    fn deltas(&self, clue: &Clue, position: usize, color: u8) -> (i32, i32) {
+        let old = self.code[position];
+        let target = clue.guess[position];
+        let exact = (color == target) as i32 - (old == target) as i32;
+        let lost = (self.counts[old as usize] <= clue.guess_counts[old as usize]) as i32;
+        let gained = (self.counts[color as usize] < clue.guess_counts[color as usize]) as i32;
+        (exact, gained - lost)
+    }
+
+    fn error_after(&self, position: usize, color: u8) -> u32 {
+        self.clues
+            .iter()
+            .enumerate()
+            .map(|(j, clue)| {
+                let (exact, total) = self.deltas(clue, position, color);
+                clue_error(
+                    clue,
+                    (self.correct_positions[j] as i32 + exact) as u16,
+                    (self.total_matches[j] as i32 + total) as u16,
+                )
+            })
+            .sum()
+    }
+
+    fn set(&mut self, position: usize, color: u8, error: u32) {
+        for (j, clue) in self.clues.iter().enumerate() {
+            let (exact, total) = self.deltas(clue, position, color);
+            self.correct_positions[j] = (self.correct_positions[j] as i32 + exact) as u16;
+            self.total_matches[j] = (self.total_matches[j] as i32 + total) as u16;
+        }
+        self.counts[self.code[position] as usize] -= 1;
+        self.counts[color as usize] += 1;
+        self.code[position] = color;
+        self.error = error;
+    }
+}
+
+// A random code with no color twice, preferring each position's allowed
+// colors. Needs at least as many colors as positions.
+fn random_distinct_code(allowed: &[Vec<u8>], base: usize, rng: &mut impl Rng) -> Vec<u8> {
+    let mut is_used = vec![false; base];
+    allowed
+        .iter()
+        .map(|colors| {
+            let free = |candidates: Vec<u8>| -> Vec<u8> {
+                candidates
+                    .into_iter()
+                    .filter(|c| !is_used[*c as usize])
+                    .collect()
+            };
+            let mut choices = free(colors.clone());
+            if choices.is_empty() {
+                choices = free((0..base as u8).collect());
+            }
+            let color = *choices.choose(rng).expect("more pegs than colors");
+            is_used[color as usize] = true;
+            color
+        })
+        .collect()
+}
+
+fn clue_error(clue: &Clue, correct_position: u16, total_matches: u16) -> u32 {
+    let total_error = match clue.is_total_known {
+        true => total_matches.abs_diff(clue.total_matches),
+        false => 0,
+    };
+    (correct_position.abs_diff(clue.correct_position) + total_error) as u32
+}
@@ -19,7 +19,7 @@
     - Uses serde_json for serializing Gamestate to JSON.
 */
 
-use crate::gamestate::Gamestate;
+use mastermind::gamestate::Gamestate;
 
 use ::std::fs;
 use std::io::{self, Write};
@@ -29,10 +29,10 @@ pub const SAVE_DIR: &str = "savegames";
 
 // Ensure save dir exists
 fn init_save_sys() {
-    if !Path::new(SAVE_DIR).exists() {
-        if let Err(e) = fs::create_dir(SAVE_DIR) {
-            eprintln!("Failed to create save directory: {}", e);
-        }
+    if !Path::new(SAVE_DIR).exists()
+        && let Err(e) = fs::create_dir(SAVE_DIR)
+    {
+        eprintln!("Failed to create save directory: {}", e);
     }
 }
 
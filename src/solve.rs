@@ -0,0 +1,167 @@
+/*
+    Solve assistant module.
+
+    Terminal frontend for the SolveAssistant: helps with a game played on a
+    real board or another program. The player types each guess with the
+    black and white counts it got, and asks what is left, what to play next
+    and how good a guess they have in mind would be.
+
+    Public API:
+    - run_solve_assistant: reads commands until the player quits.
+
+    Internal helpers (private):
+    - print_help: lists the commands.
+    - split_feedback: separates an answer's colors from its counts.
+    - recommend: asks the bot for a guess while showing its progress.
+
+    Commands:
+    - <colors> <black> <white>: add a guess and its feedback, e.g. "r r g b 1 2"
+      (just <colors> <black> under the black-pegs-only rule). Under the
+      positional rule answers are entered as counts too.
+    - list: the remaining codes (just their count above LIST_LIMIT).
+    - best: the bot's recommended next guess.
+    - eval <colors>: how a guess would split the remaining codes.
+    - undo, board, help, quit.
+
+    Notes:
+    - The rules and bot settings come from the config file or a short manual
+      setup; the bot's time limit applies to recommendations.
+*/
+
+use crate::{
+    parse::parse_colors,
+    prints::{print_evaluation, print_recommendation, print_remaining, print_solve_board},
+    thinking::watch_search,
+};
+
+use mastermind::{
+    assistant::SolveAssistant,
+    gameconfig::GameConfig,
+    strategy::SearchControl,
+    types::{CodeSpace, Color, Feedback, FeedbackRule, Line},
+};
+
+use std::io::{self, Write};
+
+const LIST_LIMIT: usize = 30;
+
+pub fn run_solve_assistant(cfg: &GameConfig) {
+    let pegs = cfg.pegs_in_a_line as usize;
+    let palette = Color::palette(cfg.number_of_colors as usize, cfg.is_empty_pegs_allowed);
+    let space = CodeSpace::new(palette.clone(), pegs)
+        .with_no_repeats(cfg.no_repeats)
+        .with_feedback_rule(cfg.feedback_rule);
+    let mut assistant = SolveAssistant::with_space(space, cfg.bot_settings);
+
+    println!(
+        "Solve assistant: {} pegs, {} colors{}{}.",
+        pegs,
+        palette.len(),
+        if cfg.no_repeats { ", no repeats" } else { "" },
+        match cfg.feedback_rule {
+            FeedbackRule::BlackOnly => ", black pegs only",
+            _ => "",
+        }
+    );
+    print_help(pegs, cfg.feedback_rule);
+
+    loop {
+        print!("solve> ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap() == 0 {
+            return;
+        }
+        let words: Vec<&str> = input.split_whitespace().collect();
+
+        match words.as_slice() {
+            [] => {}
+            ["q" | "quit" | "exit"] => return,
+            ["h" | "help"] => print_help(pegs, cfg.feedback_rule),
+            ["board"] => print_solve_board(assistant.answers()),
+            ["list"] => print_remaining(&assistant.remaining(LIST_LIMIT)),
+            ["best"] => {
+                let guess = recommend(&assistant, cfg);
+                print_recommendation(&guess);
+            }
+            ["undo"] => match assistant.undo() {
+                Some(_) => print_solve_board(assistant.answers()),
+                None => println!("The board is already empty."),
+            },
+            ["eval", colors @ ..] => match parse_colors(colors, &palette, pegs) {
+                Ok(guess) => match assistant.evaluate(&guess) {
+                    Some(evaluation) => print_evaluation(&evaluation),
+                    None => println!("Too many codes to evaluate a guess."),
+                },
+                Err(message) => println!("{}", message),
+            },
+            [_, ..] => {
+                let Some((colors, feedback)) = split_feedback(&words, cfg.feedback_rule) else {
+                    println!("Unknown command. Type 'help' for the commands.");
+                    continue;
+                };
+                match parse_colors(colors, &palette, pegs) {
+                    Ok(guess) => match assistant.add_answer(guess, feedback) {
+                        Ok(()) => print_remaining(&assistant.remaining(LIST_LIMIT)),
+                        Err(e) => println!("{}", e),
+                    },
+                    Err(message) => println!("{}", message),
+                }
+            }
+        }
+    }
+}
+
+// The colors of an answer line and the counts after them: black and white,
+// or only black under the black-pegs-only rule.
+fn split_feedback<'a>(
+    words: &'a [&'a str],
+    rule: FeedbackRule,
+) -> Option<(&'a [&'a str], Feedback)> {
+    match (rule, words) {
+        (FeedbackRule::BlackOnly, [colors @ .., black]) => Some((
+            colors,
+            Feedback {
+                correct_position: black.parse().ok()?,
+                correct_color: 0,
+            },
+        )),
+        (FeedbackRule::BlackOnly, _) => None,
+        (_, [colors @ .., black, white]) => Some((
+            colors,
+            Feedback {
+                correct_position: black.parse().ok()?,
+                correct_color: white.parse().ok()?,
+            },
+        )),
+        _ => None,
+    }
+}
+
+fn print_help(pegs: usize, rule: FeedbackRule) {
+    println!("Commands:");
+    match rule {
+        FeedbackRule::BlackOnly => println!(
+            "  <{} colors> <black>       add a guess and its black pegs (e.g. 'r r g b 1')",
+            pegs
+        ),
+        _ => println!(
+            "  <{} colors> <black> <white>  add a guess and its feedback (e.g. 'r r g b 1 2')",
+            pegs
+        ),
+    }
+    println!("  list                     show the codes that still fit");
+    println!("  best                     recommend the next guess");
+    println!(
+        "  eval <{} colors>          rate a guess you're considering",
+        pegs
+    );
+    println!("  undo / board / help / quit");
+}
+
+// Recommendations can take as long as a bot turn, so they get the same
+// progress line and Esc/Ctrl-C to stop early.
+fn recommend(assistant: &SolveAssistant, cfg: &GameConfig) -> Line {
+    let control = SearchControl::new(cfg.bot_settings.time_limit());
+    watch_search(&control, || assistant.recommend(&control))
+}
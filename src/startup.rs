@@ -1,67 +1,36 @@
 /*
     Startup module.
 
-    Responsible for creating the initial game state when the program begins.
+    Responsible for creating the initial game engine when the program begins.
 
     It handles:
     - Loading an existing save file when the user chooses "Load Game".
-    - Creating a fresh Gamestate when starting a new game.
-    - Initializing an optional Bot based on the selected GameMode.
+    - Creating a fresh Engine (Gamestate and optional bot) when starting a new game.
+    - Seating a custom BreakerStrategy as the bot, if one is given.
+    - Asking the human Code Maker for the first secret code when the mode needs one.
 
     Public API:
     - handle_startup: main entry point for all startup logic.
-
-    Internal helpers (private):
-    - init_gamestate: builds a new Gamestate from a GameConfig.
-    - init_bot: creates a Bot when the selected GameMode requires one.
 */
 
-use crate::{
-    bot::Bot,
-    gameconfig::GameConfig,
-    gamestate::Gamestate,
-    loadgame::handle_load,
-    types::{GameMode, Line},
-    usersetup::StartupAction,
-};
+use crate::{loadgame::handle_load, twoplayer::get_human_target_line, usersetup::StartupAction};
+
+use mastermind::{engine::Engine, strategy::BreakerStrategy};
 
-pub fn handle_startup(action: StartupAction) -> (Gamestate, Option<Bot>) {
+// `breaker` replaces the built-in Bot in the SpectateBot and PlayerVsBot seats.
+pub fn handle_startup(action: StartupAction, breaker: Option<Box<dyn BreakerStrategy>>) -> Engine {
     match action {
-        StartupAction::LoadGame => {
-            let gs = handle_load();
-            let bot = init_bot(&gs);
-            (gs, bot)
-        }
+        StartupAction::LoadGame => Engine::from_gamestate(handle_load(), breaker),
         StartupAction::NewGame(cfg) => {
-            let gs = init_gamestate(&cfg);
-            let bot = init_bot(&gs);
-
-            (gs, bot)
+            let mut engine = Engine::new(&cfg, breaker);
+            for warning in &engine.config_warnings {
+                println!("{}", warning);
+            }
+            if engine.needs_human_target() {
+                let target = get_human_target_line(&engine.gamestate);
+                engine.set_target(target);
+            }
+            engine
         }
     }
 }
-
-fn init_gamestate(cfg: &GameConfig) -> Gamestate {
-    let no_of_pegs = cfg.pegs_in_a_line as usize;
-
-    Gamestate::new(
-        cfg.game_mode,
-        cfg.number_of_guesses,
-        no_of_pegs,
-        Line::empty(no_of_pegs),
-        cfg.is_empty_pegs_allowed,
-    )
-}
-
-fn init_bot(gamestate: &Gamestate) -> Option<Bot> {
-    let needs_bot = gamestate.game_mode == GameMode::SpectateBot
-        || gamestate.game_mode == GameMode::PlayerVsBot;
-    if needs_bot {
-        Some(Bot::new(
-            gamestate.is_empty_allowed,
-            gamestate.pegs_in_a_line,
-        ))
-    } else {
-        None
-    }
-}
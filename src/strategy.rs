@@ -0,0 +1,157 @@
+/*
+    Strategy module.
+
+    Defines the BreakerStrategy trait, the interface the engine uses to let a
+    bot take the Code Breaker seat. The built-in minimax Bot is one
+    implementation; third-party solvers can implement it to play in the
+    Spectate Bot and Player vs Bot seats without changing the game loop.
+
+    Public API:
+    - BreakerStrategy::next_guess: choose the next guess to play.
+    - BreakerStrategy::next_guess_within: the same, under a SearchControl; by
+      default it ignores the control and calls next_guess.
+    - BreakerStrategy::observe_feedback: learn from the feedback to a guess.
+    - BreakerStrategy::observe_flags: the same from the guess's flag pegs; by
+      default their black and white counts go to observe_feedback.
+    - BreakerStrategy::reset_for_round: forget the previous round's guesses.
+    - BreakerStrategy::next_batch_within / final_guess_within: the two moves of
+      a static round; by default a batch of one guess and the usual next guess.
+    - SearchControl: a time limit, a cancel flag and a progress counter for one
+      guess, shared between the thinking bot and the frontend watching it.
+
+    Notes:
+    - A strategy is built for one rule set (pegs, palette); the engine keeps the
+      same instance for the whole game and resets it between rounds.
+    - observe_flags is called once after every next_guess, with the guess
+      that was actually played (observe_feedback instead when a human scores
+      the bot by hand). After a batch it is called for each of its
+      guesses in order, once the whole batch has been played.
+    - Strategies must be Send so the engine can run them in its own rayon pool.
+    - A strategy that is told to stop still returns a legal guess: the best it
+      found so far, or any fallback it has.
+*/
+
+use crate::{
+    gamelogic::feedback_from_flags,
+    types::{Feedback, Line},
+};
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use std::time::{Duration, Instant};
+
+pub trait BreakerStrategy: Send {
+    fn next_guess(&mut self) -> Line;
+
+    fn next_guess_within(&mut self, control: &SearchControl) -> Line {
+        let _ = control;
+        self.next_guess()
+    }
+
+    fn observe_feedback(&mut self, guess: &Line, feedback: Feedback);
+
+    // The answer as the flag pegs on the board show it. Under the positional
+    // rule these are marks per peg; a strategy that doesn't override this
+    // only learns their counts.
+    fn observe_flags(&mut self, guess: &Line, flags: &Line) {
+        self.observe_feedback(guess, feedback_from_flags(flags));
+    }
+
+    fn reset_for_round(&mut self);
+
+    // Static Mastermind: between 1 and `max_guesses` guesses played together,
+    // before any of their feedback is known.
+    fn next_batch_within(&mut self, max_guesses: usize, control: &SearchControl) -> Vec<Line> {
+        let _ = max_guesses;
+        vec![self.next_guess_within(control)]
+    }
+
+    // The one guess after a static batch, which only scores if it is the code.
+    fn final_guess_within(&mut self, control: &SearchControl) -> Line {
+        self.next_guess_within(control)
+    }
+}
+
+// Clones share the same flag and counters, so a frontend can keep one to
+// watch and cancel the search while the bot runs with another.
+#[derive(Clone)]
+pub struct SearchControl {
+    started: Instant,
+    deadline: Option<Instant>,
+    is_cancelled: Arc<AtomicBool>,
+    done: Arc<AtomicU64>,
+    total: Arc<AtomicU64>,
+}
+
+impl SearchControl {
+    pub fn new(time_limit: Option<Duration>) -> Self {
+        let started = Instant::now();
+        SearchControl {
+            started,
+            deadline: time_limit.map(|limit| started + limit),
+            is_cancelled: Arc::new(AtomicBool::new(false)),
+            done: Arc::new(AtomicU64::new(0)),
+            total: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+
+    pub fn cancel(&self) {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.is_cancelled.load(Ordering::Relaxed)
+    }
+
+    // True once the search was cancelled or ran out of time.
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled()
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline - self.started)
+    }
+
+    // Progress is counted in whatever units the strategy works in (guesses
+    // scored, searches run); a total of 0 means the amount of work is unknown.
+    pub fn set_total(&self, total: u64) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, amount: u64) {
+        self.done.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    pub fn progress(&self) -> (u64, u64) {
+        (
+            self.done.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        )
+    }
+
+    // Estimated time left from the progress so far, if there is any.
+    pub fn eta(&self) -> Option<Duration> {
+        let (done, total) = self.progress();
+        if done == 0 || total == 0 {
+            return None;
+        }
+        let per_unit = self.elapsed().as_secs_f64() / done as f64;
+        Some(Duration::from_secs_f64(
+            per_unit * total.saturating_sub(done) as f64,
+        ))
+    }
+}
@@ -0,0 +1,215 @@
+/*
+    Symmetry module.
+
+    Tracks which relabelings of colors and reorderings of positions leave every
+    guess made so far unchanged. Such a symmetry also maps the set of codes
+    consistent with the feedback onto itself, so guesses that are images of
+    each other are equally good and only one per equivalence class needs to
+    be evaluated.
+
+    Public API:
+    - Symmetries::new: the full group (any position order, any color relabeling)
+      that applies before the first guess.
+    - Symmetries::after_guess: the subgroup that also leaves a new guess unchanged.
+    - Symmetries::after_marks: the subgroup that also leaves a guess's
+      positional marks unchanged (positional feedback rule).
+    - Symmetries::is_trivial: true once no symmetry is left.
+    - Symmetries::is_canonical: true if a code is the smallest of its class.
+    - Symmetries::representatives: one code per class, from an iterator of codes.
+
+    Notes:
+    - Colors that no guess has used yet can be relabeled freely; they are kept
+      implicit instead of enumerating every permutation of them.
+    - Position reorderings are only tracked up to MAX_PERMUTED_WIDTH pegs
+      (width! grows too fast); wider codes only get color symmetries.
+    - Word games (a CodeSpace with a dictionary) have none.
+*/
+
+use crate::{
+    gamelogic::MAX_SYMBOLS,
+    types::{Code, CodeSpace, MAX_CODE_WIDTH},
+};
+
+pub const MAX_PERMUTED_WIDTH: usize = 8;
+
+const UNMAPPED: u8 = u8::MAX;
+
+#[derive(Clone)]
+struct Symmetry {
+    // position i of the image takes the peg at positions[i]...
+    positions: Vec<usize>,
+    // ...relabeled through colors (UNMAPPED for colors no guess has used yet).
+    colors: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct Symmetries {
+    space: CodeSpace,
+    symmetries: Vec<Symmetry>,
+    used_colors: Vec<bool>,
+}
+
+impl Symmetries {
+    // A word relabeled or reordered is rarely another word, so a dictionary
+    // space starts out with no symmetries.
+    pub fn new(space: &CodeSpace) -> Self {
+        let base = space.palette.len();
+        if space.dictionary.is_some() {
+            return Symmetries {
+                space: space.clone(),
+                symmetries: Vec::new(),
+                used_colors: vec![true; base],
+            };
+        }
+        let mut position_orders = Vec::new();
+        if space.width <= MAX_PERMUTED_WIDTH {
+            permutations(&mut (0..space.width).collect(), 0, &mut position_orders);
+        } else {
+            position_orders.push((0..space.width).collect());
+        }
+
+        let symmetries = position_orders
+            .into_iter()
+            .map(|positions| Symmetry {
+                positions,
+                colors: vec![UNMAPPED; base],
+            })
+            .collect();
+
+        Symmetries {
+            space: space.clone(),
+            symmetries,
+            used_colors: vec![false; base],
+        }
+    }
+
+    pub fn after_guess(&self, guess: Code) -> Self {
+        let width = self.space.width;
+        let mut digits = [0; MAX_CODE_WIDTH];
+        self.space.digits(guess, &mut digits[..width]);
+        let guess = &digits[..width];
+
+        let symmetries = self
+            .symmetries
+            .iter()
+            .filter_map(|symmetry| self.extend_to_fix(symmetry, guess))
+            .collect();
+
+        let mut used_colors = self.used_colors.clone();
+        for digit in guess {
+            used_colors[*digit as usize] = true;
+        }
+
+        Symmetries {
+            space: self.space.clone(),
+            symmetries,
+            used_colors,
+        }
+    }
+
+    // Under the positional rule the answer is tied to positions too: only
+    // reorderings that map each peg onto one with the same mark still map
+    // the consistent codes onto themselves.
+    pub fn after_marks(&self, marks: &[u8]) -> Self {
+        let symmetries = self
+            .symmetries
+            .iter()
+            .filter(|symmetry| {
+                symmetry
+                    .positions
+                    .iter()
+                    .enumerate()
+                    .all(|(i, position)| marks[*position] == marks[i])
+            })
+            .cloned()
+            .collect();
+        Symmetries {
+            space: self.space.clone(),
+            symmetries,
+            used_colors: self.used_colors.clone(),
+        }
+    }
+
+    pub fn is_trivial(&self) -> bool {
+        let free_colors = self.used_colors.iter().filter(|used| !**used).count();
+        self.symmetries.len() <= 1 && free_colors <= 1
+    }
+
+    pub fn is_canonical(&self, code: Code) -> bool {
+        if self.is_trivial() {
+            return true;
+        }
+        let width = self.space.width;
+        let mut digits = [0; MAX_CODE_WIDTH];
+        let mut image = [0; MAX_CODE_WIDTH];
+        self.space.digits(code, &mut digits[..width]);
+
+        self.symmetries.iter().all(|symmetry| {
+            self.apply(symmetry, &digits[..width], &mut image[..width]);
+            image[..width] >= digits[..width]
+        })
+    }
+
+    pub fn representatives(&self, codes: impl Iterator<Item = Code>) -> Vec<Code> {
+        codes.filter(|code| self.is_canonical(*code)).collect()
+    }
+
+    // `symmetry` extended so that it also maps `guess` onto itself, if possible.
+    fn extend_to_fix(&self, symmetry: &Symmetry, guess: &[u8]) -> Option<Symmetry> {
+        let mut colors = symmetry.colors.clone();
+        let mut is_target_taken: Vec<bool> = self.used_colors.clone();
+        for (i, position) in symmetry.positions.iter().enumerate() {
+            let from = guess[*position] as usize;
+            let to = guess[i];
+            if self.used_colors[from] {
+                if colors[from] != to {
+                    return None;
+                }
+            } else if colors[from] == UNMAPPED {
+                if is_target_taken[to as usize] {
+                    return None;
+                }
+                colors[from] = to;
+                is_target_taken[to as usize] = true;
+            } else if colors[from] != to {
+                return None;
+            }
+        }
+        Some(Symmetry {
+            positions: symmetry.positions.clone(),
+            colors,
+        })
+    }
+
+    // Writes the image of `digits` under `symmetry`, with unused colors relabeled
+    // to the smallest unused colors in order of first appearance.
+    fn apply(&self, symmetry: &Symmetry, digits: &[u8], image: &mut [u8]) {
+        let mut free_labels = [UNMAPPED; MAX_SYMBOLS];
+        let mut next_free = self.used_colors.iter().position(|used| !*used);
+        for (i, position) in symmetry.positions.iter().enumerate() {
+            let color = digits[*position] as usize;
+            image[i] = if self.used_colors[color] {
+                symmetry.colors[color]
+            } else {
+                if free_labels[color] == UNMAPPED {
+                    let label = next_free.expect("more free colors than palette");
+                    free_labels[color] = label as u8;
+                    next_free = (label + 1..self.used_colors.len()).find(|c| !self.used_colors[*c]);
+                }
+                free_labels[color]
+            };
+        }
+    }
+}
+
+fn permutations(items: &mut Vec<usize>, start: usize, out: &mut Vec<Vec<usize>>) {
+    if start == items.len() {
+        out.push(items.clone());
+        return;
+    }
+    for i in start..items.len() {
+        items.swap(start, i);
+        permutations(items, start + 1, out);
+        items.swap(start, i);
+    }
+}
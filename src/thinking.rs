@@ -0,0 +1,116 @@
+/*
+    Bot thinking module.
+
+    Runs the bot's turn while showing a live progress line, and lets the
+    player stop the search early with Esc or Ctrl-C; the bot then plays the
+    best guess it has found so far.
+
+    Public API:
+    - bot_guess: plays the bot's turn through the engine, watching it from a
+      second thread.
+    - bot_batch: the same for the batch of guesses a static round starts with.
+    - bot_proposal: the same search, but only returns the guess so a human
+      Code Maker can score it by hand.
+
+    - watch_search: runs any search under a SearchControl the same way (used
+      by the solve assistant).
+
+    Internal helpers (private):
+    - watch: polls the keyboard and redraws the progress line until the bot is done.
+    - is_stop_key: whether a key press asks the bot to stop.
+
+    Notes:
+    - The terminal is in raw mode while the bot thinks, so key presses arrive
+      immediately and Ctrl-C is read as a key instead of killing the game.
+    - The progress line only appears once a guess takes longer than
+      SHOW_PROGRESS_AFTER, so quick guesses don't flicker, and it is erased
+      before the board is drawn.
+    - If raw mode isn't available (e.g. input is piped), the progress line is
+      still shown but the search can't be cancelled.
+*/
+
+use crate::prints::print_thinking_progress;
+
+use mastermind::{engine::Engine, strategy::SearchControl, types::Line};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{self, Clear, ClearType},
+};
+use std::io::{Write, stdout};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+const SHOW_PROGRESS_AFTER: Duration = Duration::from_millis(300);
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+pub fn bot_guess(engine: &mut Engine) {
+    let control = engine.search_control();
+    watch_search(&control, || {
+        engine.play_bot_turn_within(&control);
+    });
+}
+
+pub fn bot_batch(engine: &mut Engine) {
+    let control = engine.search_control();
+    watch_search(&control, || {
+        engine.play_bot_batch_within(&control);
+    });
+}
+
+pub fn bot_proposal(engine: &mut Engine) -> Option<Line> {
+    let control = engine.search_control();
+    watch_search(&control, || engine.bot_guess_within(&control))
+}
+
+pub fn watch_search<T>(control: &SearchControl, search: impl FnOnce() -> T) -> T {
+    let is_done = AtomicBool::new(false);
+    let can_cancel = terminal::enable_raw_mode().is_ok();
+
+    let result = thread::scope(|scope| {
+        scope.spawn(|| watch(control, &is_done, can_cancel));
+        let result = search();
+        is_done.store(true, Ordering::Relaxed);
+        result
+    });
+
+    if can_cancel {
+        terminal::disable_raw_mode().ok();
+    }
+    result
+}
+
+fn watch(control: &SearchControl, is_done: &AtomicBool, can_cancel: bool) {
+    let mut is_shown = false;
+    while !is_done.load(Ordering::Relaxed) {
+        if can_cancel {
+            if event::poll(REFRESH_INTERVAL).unwrap_or(false)
+                && let Ok(Event::Key(key)) = event::read()
+                && is_stop_key(&key)
+            {
+                control.cancel();
+            }
+        } else {
+            thread::sleep(REFRESH_INTERVAL);
+        }
+
+        if control.elapsed() >= SHOW_PROGRESS_AFTER && !is_done.load(Ordering::Relaxed) {
+            print_thinking_progress(control);
+            is_shown = true;
+        }
+    }
+
+    if is_shown {
+        print!("\r");
+        execute!(stdout(), Clear(ClearType::CurrentLine)).ok();
+        stdout().flush().ok();
+    }
+}
+
+fn is_stop_key(key: &KeyEvent) -> bool {
+    key.kind == KeyEventKind::Press
+        && (key.code == KeyCode::Esc
+            || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)))
+}
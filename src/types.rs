@@ -6,8 +6,13 @@
 
     Public API:
     - GameMode: enumerates possible game modes (Practice, TwoPlayer, PlayerVsBot, SpectateBot).
-    - Color: enumerates possible peg colors including Empty.
+    - Color: enumerates possible peg colors including Empty, and the letters
+      of word games.
         - Color::all_colors: returns a Vec of all colors.
+        - Color::palette: returns the colors in play for a given number of colors.
+        - Color::letters: the palette of a word game, 'a' to 'z'.
+        - Color::symbol: a small distinct number per color, for counting.
+    - MAX_COLORS / DEFAULT_COLORS: bounds for the configurable number of colors.
     - Peg: represents a single peg with a color.
         - Peg::new: creates a peg with Color::Empty.
     - Line: represents a sequence of pegs (a full guess or target).
@@ -16,15 +21,59 @@
         - Implements Display for human-readable printing.
     - Feedback: represents the result of comparing a guess to a target.
         - Feedback::empty: creates a feedback with zero correct positions/colors.
+        - Feedback::encode / decode: pack feedback into a small integer for a given width.
+    - FeedbackRule: how much of the feedback is revealed (Standard, BlackOnly
+      for Black-peg Mastermind, or Positional marks per peg as in Wordle);
+      FeedbackRule::apply hides what the rule doesn't count.
+    - MAX_POSITIONAL_WIDTH: the most pegs the positional rule is played with.
+    - Code: a line packed as its base-N rank, where N is the palette size.
+    - MAX_CODE_WIDTH: the most pegs a Code can hold.
+    - CodeSpace: all lines of a given width over a palette, optionally only
+      those without a repeated color (Bulls and Cows rules), and the
+      FeedbackRule they are scored under.
+        - CodeSpace::with_no_repeats: the same space with or without repeats.
+        - CodeSpace::with_feedback_rule: the same space under another rule.
+        - CodeSpace::with_max_lies: the same space with up to that many false
+          answers per round (read by the breaker bot, not by scoring).
+        - CodeSpace::with_dictionary: the same space limited to the words of
+          a dictionary (word games).
+        - CodeSpace::score: score_digits with the rule applied.
+        - CodeSpace::answer / answer_of_flags: the full answer under the rule
+          (per-position marks included) packed into a number below
+          answer_count; win_answer is the all-correct one, and
+          flags_of_answer turns an answer back into flag pegs.
+        - CodeSpace::size: number of Code values, i.e. base^width (None if it
+          doesn't fit in a Code).
+        - CodeSpace::count: number of codes in play; the same as size unless
+          repeats are ruled out.
+        - CodeSpace::codes: every code in play, in ascending order.
+        - CodeSpace::rank / unrank: convert between Line and Code.
+        - CodeSpace::digits: unpack a Code into palette indices.
 
     Notes:
     - All types derive traits to support cloning, comparison, hashing, serialization,
       and debugging.
     - Color, Peg, Line, and Feedback are designed to integrate seamlessly with
       the game logic and bot computations.
+    - The first peg of a line is the most significant digit of its Code, so codes
+      sort in the same order as lines compared peg by peg in palette order.
+    - Without repeats the Codes keep their usual base-N rank; the space just
+      skips the ones with a repeated digit, so size stays the bound for any
+      Code and count is what callers enumerate.
+    - With a dictionary a Code is a word's index instead of a rank; digits
+      and rank go through the dictionary, and size is the number of words.
 */
 
+use crate::{
+    gamelogic::{
+        feedback_from_flags, flags_from_feedback, flags_from_marks, mark_digits, marks_from_flags,
+        score_digits,
+    },
+    words::Dictionary,
+};
+
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 // Begin GameMode
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
@@ -36,7 +85,25 @@ pub enum GameMode {
 } // End GameMode
 
 // Begin Color
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub const DEFAULT_COLORS: usize = 6;
+pub const MAX_COLORS: usize = 10;
+
+// Playable colors in the order they are added to the palette.
+// Base rules use the first DEFAULT_COLORS, Super Mastermind the first 8.
+const PEG_COLORS: [Color; MAX_COLORS] = [
+    Color::White,
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Yellow,
+    Color::Orange,
+    Color::Purple,
+    Color::Cyan,
+    Color::Magenta,
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Color {
     Empty,
     White,
@@ -45,21 +112,75 @@ pub enum Color {
     Green,
     Blue,
     Yellow,
+    Orange,
+    Purple,
+    Cyan,
+    Magenta,
+    // The letters of the word variant, 0 for 'a'.
+    Letter(u8),
 }
 
+pub const LETTERS: usize = 26;
+
 impl Color {
     pub fn all_colors() -> Vec<Color> {
-        let colors = vec![
-            Color::Empty,
-            Color::White,
-            Color::Black,
-            Color::Red,
-            Color::Green,
-            Color::Blue,
-            Color::Yellow,
-        ];
+        let mut colors = vec![Color::Empty];
+        colors.extend(PEG_COLORS);
+        colors
+    }
+
+    // Colors in play: the first `number_of_colors` peg colors, with Empty last if allowed.
+    pub fn palette(number_of_colors: usize, is_empty_allowed: bool) -> Vec<Color> {
+        let mut colors: Vec<Color> = PEG_COLORS
+            .iter()
+            .take(number_of_colors.min(MAX_COLORS))
+            .copied()
+            .collect();
+        if is_empty_allowed {
+            colors.push(Color::Empty);
+        }
         colors
     }
+
+    // The word variant's palette: palette index i is letter i.
+    pub fn letters() -> Vec<Color> {
+        (0..LETTERS as u8).map(Color::Letter).collect()
+    }
+
+    // A distinct number below MAX_SYMBOLS for every color, for counting.
+    pub fn symbol(&self) -> usize {
+        match self {
+            Color::Empty => 0,
+            Color::Letter(letter) => 1 + MAX_COLORS + *letter as usize,
+            color => {
+                1 + PEG_COLORS
+                    .iter()
+                    .position(|c| c == color)
+                    .expect("every other color is a peg color")
+            }
+        }
+    }
+}
+
+// Colors print by name, letters as the letter.
+impl std::fmt::Debug for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Color::Empty => "Empty",
+            Color::White => "White",
+            Color::Black => "Black",
+            Color::Red => "Red",
+            Color::Green => "Green",
+            Color::Blue => "Blue",
+            Color::Yellow => "Yellow",
+            Color::Orange => "Orange",
+            Color::Purple => "Purple",
+            Color::Cyan => "Cyan",
+            Color::Magenta => "Magenta",
+            Color::Letter(letter) => return write!(f, "{}", (b'A' + letter) as char),
+        };
+        write!(f, "{}", name)
+    }
 } // End Color
 
 // Begin Peg
@@ -74,6 +195,12 @@ impl Peg {
             color: Color::Empty,
         }
     }
+}
+
+impl Default for Peg {
+    fn default() -> Self {
+        Self::new()
+    }
 } // End Peg
 
 // Begin Line
@@ -126,4 +253,275 @@ impl Feedback {
             correct_color: 0,
         }
     }
+
+    // Packs feedback into a number below (width + 1)^2, e.g. for lookup tables.
+    pub fn encode(&self, width: usize) -> usize {
+        self.correct_position as usize * (width + 1) + self.correct_color as usize
+    }
+
+    pub fn decode(encoded: usize, width: usize) -> Self {
+        Feedback {
+            correct_position: (encoded / (width + 1)) as u8,
+            correct_color: (encoded % (width + 1)) as u8,
+        }
+    }
+}
+
+// How much of the full black/white answer the Code Breaker is told.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum FeedbackRule {
+    #[default]
+    Standard,
+    // Black-peg Mastermind: only exact matches are reported.
+    BlackOnly,
+    // Wordle-style: every position is marked exact, present elsewhere or
+    // absent. The counts are the same as Standard's.
+    Positional,
+}
+
+// Positional marks take 3^pegs answers, so the rule is limited to this width.
+pub const MAX_POSITIONAL_WIDTH: usize = 8;
+
+impl FeedbackRule {
+    pub fn apply(&self, feedback: Feedback) -> Feedback {
+        match self {
+            FeedbackRule::Standard | FeedbackRule::Positional => feedback,
+            FeedbackRule::BlackOnly => Feedback {
+                correct_color: 0,
+                ..feedback
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for FeedbackRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FeedbackRule::Standard => write!(f, "Standard"),
+            FeedbackRule::BlackOnly => write!(f, "Black pegs only"),
+            FeedbackRule::Positional => write!(f, "Positional (Wordle-style)"),
+        }
+    }
 } // End Feedback
+
+// Begin Code
+pub type Code = u64;
+
+// A Code has at most 64 digits (base 2), so unpacked codes fit in a fixed array.
+pub const MAX_CODE_WIDTH: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeSpace {
+    pub palette: Vec<Color>,
+    pub width: usize,
+    pub no_repeats: bool,
+    pub feedback_rule: FeedbackRule,
+    // How many answers in a round may be false (0 for an honest Maker).
+    pub max_lies: u8,
+    // Word games: the codes are the dictionary's words, by index.
+    pub dictionary: Option<Arc<Dictionary>>,
+}
+
+impl CodeSpace {
+    pub fn new(palette: Vec<Color>, width: usize) -> Self {
+        CodeSpace {
+            palette,
+            width,
+            no_repeats: false,
+            feedback_rule: FeedbackRule::Standard,
+            max_lies: 0,
+            dictionary: None,
+        }
+    }
+
+    // Pair with Color::letters as the palette and the word length as width.
+    pub fn with_dictionary(self, dictionary: Option<Arc<Dictionary>>) -> Self {
+        CodeSpace { dictionary, ..self }
+    }
+
+    pub fn with_no_repeats(self, no_repeats: bool) -> Self {
+        CodeSpace { no_repeats, ..self }
+    }
+
+    pub fn with_feedback_rule(self, feedback_rule: FeedbackRule) -> Self {
+        CodeSpace {
+            feedback_rule,
+            ..self
+        }
+    }
+
+    pub fn with_max_lies(self, max_lies: u8) -> Self {
+        CodeSpace { max_lies, ..self }
+    }
+
+    // The feedback a guess gets against a target under this space's rule,
+    // both given as palette indices.
+    pub fn score(&self, target: &[u8], guess: &[u8]) -> Feedback {
+        self.feedback_rule.apply(score_digits(target, guess))
+    }
+
+    // The answer a guess gets against a target under this space's rule,
+    // packed into a number below answer_count: Feedback::encode for the
+    // counting rules, the per-position marks for the positional rule.
+    pub fn answer(&self, target: &[u8], guess: &[u8]) -> usize {
+        match self.feedback_rule {
+            FeedbackRule::Positional => mark_digits(target, guess),
+            _ => self.score(target, guess).encode(self.width),
+        }
+    }
+
+    // The same for an answer shown as flag pegs on the board.
+    pub fn answer_of_flags(&self, flags: &Line) -> usize {
+        match self.feedback_rule {
+            FeedbackRule::Positional => marks_from_flags(flags),
+            _ => self
+                .feedback_rule
+                .apply(feedback_from_flags(flags))
+                .encode(self.width),
+        }
+    }
+
+    // The flag pegs that show a packed answer on the board.
+    pub fn flags_of_answer(&self, answer: usize) -> Line {
+        match self.feedback_rule {
+            FeedbackRule::Positional => flags_from_marks(answer, self.width),
+            _ => flags_from_feedback(Feedback::decode(answer, self.width), self.width),
+        }
+    }
+
+    pub fn answer_count(&self) -> usize {
+        match self.feedback_rule {
+            FeedbackRule::Positional => 3usize.saturating_pow(self.width as u32),
+            _ => (self.width + 1) * (self.width + 1),
+        }
+    }
+
+    // The answer to a guess that is the code.
+    pub fn win_answer(&self) -> usize {
+        match self.feedback_rule {
+            FeedbackRule::Positional => self.answer_count() - 1,
+            _ => Feedback {
+                correct_position: self.width as u8,
+                correct_color: 0,
+            }
+            .encode(self.width),
+        }
+    }
+
+    pub fn base(&self) -> u64 {
+        self.palette.len() as u64
+    }
+
+    pub fn size(&self) -> Option<u64> {
+        if let Some(dictionary) = &self.dictionary {
+            return Some(dictionary.len() as u64);
+        }
+        self.base().checked_pow(self.width as u32)
+    }
+
+    // Without repeats: base * (base - 1) * ... over width factors, 0 if the
+    // code is wider than the palette.
+    pub fn count(&self) -> Option<u64> {
+        if self.dictionary.is_some() {
+            return Some(self.codes().len() as u64);
+        }
+        if !self.no_repeats {
+            return self.size();
+        }
+        if self.width as u64 > self.base() {
+            return Some(0);
+        }
+        (0..self.width as u64).try_fold(1u64, |count, i| count.checked_mul(self.base() - i))
+    }
+
+    // Panics if the space doesn't fit in a Code.
+    pub fn codes(&self) -> Vec<Code> {
+        if let Some(dictionary) = &self.dictionary {
+            return (0..dictionary.len())
+                .filter(|index| !self.no_repeats || !has_repeat(dictionary.letters(*index)))
+                .map(|index| index as Code)
+                .collect();
+        }
+        let size = self.size().expect("search space is too large to enumerate");
+        if !self.no_repeats {
+            return (0..size).collect();
+        }
+        let mut codes = Vec::with_capacity(self.count().unwrap_or(0) as usize);
+        let mut is_used = vec![false; self.palette.len()];
+        self.push_distinct(0, 0, &mut is_used, &mut codes);
+        codes
+    }
+
+    // Depth-first over the pegs, lowest digit first, so codes come out sorted.
+    fn push_distinct(
+        &self,
+        prefix: Code,
+        depth: usize,
+        is_used: &mut [bool],
+        codes: &mut Vec<Code>,
+    ) {
+        if depth == self.width {
+            codes.push(prefix);
+            return;
+        }
+        for digit in 0..is_used.len() {
+            if is_used[digit] {
+                continue;
+            }
+            is_used[digit] = true;
+            self.push_distinct(
+                prefix * self.base() + digit as Code,
+                depth + 1,
+                is_used,
+                codes,
+            );
+            is_used[digit] = false;
+        }
+    }
+
+    pub fn index_of(&self, color: Color) -> Option<usize> {
+        self.palette.iter().position(|c| *c == color)
+    }
+
+    // Panics if a peg's color is not part of the palette, or in a word game
+    // if the line isn't a word.
+    pub fn rank(&self, line: &Line) -> Code {
+        if let Some(dictionary) = &self.dictionary {
+            return dictionary
+                .index_of_line(line)
+                .expect("line is not in the word list") as Code;
+        }
+        line.pegs.iter().fold(0, |code, peg| {
+            let index = self
+                .index_of(peg.color)
+                .expect("peg color is not in the palette");
+            code * self.base() + index as Code
+        })
+    }
+
+    pub fn unrank(&self, code: Code) -> Line {
+        let mut digits = vec![0; self.width];
+        self.digits(code, &mut digits);
+        Line::new(digits.iter().map(|d| self.palette[*d as usize]).collect())
+    }
+
+    // Writes the palette index of each peg into `digits` (first peg first).
+    pub fn digits(&self, mut code: Code, digits: &mut [u8]) {
+        if let Some(dictionary) = &self.dictionary {
+            digits.copy_from_slice(dictionary.letters(code as usize));
+            return;
+        }
+        let base = self.base();
+        for digit in digits.iter_mut().rev() {
+            *digit = (code % base) as u8;
+            code /= base;
+        }
+    }
+}
+
+fn has_repeat(digits: &[u8]) -> bool {
+    digits
+        .iter()
+        .enumerate()
+        .any(|(i, digit)| digits[..i].contains(digit))
+} // End Code
@@ -5,43 +5,56 @@
     or load an existing one, and whether to use a config file or manual settings.
 
     Public API:
-    - StartupAction: enum representing the user's choice:
+    - Setup: enum representing the user's choice:
+        - Game(StartupAction): play a game, new or loaded.
+        - Solve(GameConfig): run the solve assistant with the rules (pegs, colors)
+          and bot settings from the configuration.
+    - StartupAction: how the game starts:
         - NewGame(GameConfig): start a new game with the specified configuration.
         - LoadGame: load a previously saved game.
-    - user_setup: interactively asks the user for choices and returns a StartupAction.
+    - user_setup: interactively asks the user for choices and returns a Setup.
 
     Notes:
     - If the user opts to load a game, no further configuration is requested.
+    - The solve assistant uses the same config file, or asks only for the code rules.
     - If the user chooses a new game, the module checks for a config file first,
       falling back to manual configuration if none is found or invalid.
     - All input is validated with loops until a valid response is provided.
 */
 
-use crate::{gameconfig::GameConfig, manualconfig::get_manual_config};
+use crate::manualconfig::{get_manual_config, get_manual_solve_config};
+
+use mastermind::gameconfig::GameConfig;
 use std::io::{self, Write};
 
+pub enum Setup {
+    Game(StartupAction),
+    Solve(GameConfig),
+}
+
 pub enum StartupAction {
     NewGame(GameConfig),
     LoadGame,
 }
 
-pub fn user_setup() -> StartupAction {
-    println!("(N)ew Game or (L)oad Game?");
+pub fn user_setup() -> Setup {
+    println!("(N)ew Game, (L)oad Game or (S)olve assistant for a game played elsewhere?");
     io::stdout().flush().unwrap();
 
-    let load_game: bool = loop {
+    let (load_game, solve) = loop {
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
         match input.trim().to_lowercase().as_str() {
-            "n" | "new" => break false,
-            "l" | "load" => break true,
-            _ => println!("Please enter 'n' or 'l':"),
+            "n" | "new" => break (false, false),
+            "l" | "load" => break (true, false),
+            "s" | "solve" => break (false, true),
+            _ => println!("Please enter 'n', 'l' or 's':"),
         }
     };
 
     // If Load Game, return immediately. Don't ask for config.
     if load_game {
-        return StartupAction::LoadGame;
+        return Setup::Game(StartupAction::LoadGame);
     }
 
     println!("Do you want to use the config file for game settings? (y/n)");
@@ -60,10 +73,20 @@ pub fn user_setup() -> StartupAction {
     if use_config_file {
         // Try to load. If successful, return NewGame with that config.
         // If fail, fall through to manual.
-        if let Some(cfg) = GameConfig::load_from_file("config.txt") {
-            return StartupAction::NewGame(cfg);
+        if let Some((cfg, warnings)) = GameConfig::load_from_file("config.txt") {
+            for warning in &warnings {
+                println!("{}", warning);
+            }
+            println!("Loaded configuration from config.txt!");
+            return match solve {
+                true => Setup::Solve(cfg),
+                false => Setup::Game(StartupAction::NewGame(cfg)),
+            };
         }
         println!("No config file found or invalid configuration. Entering manual setup...");
     }
-    StartupAction::NewGame(get_manual_config())
+    match solve {
+        true => Setup::Solve(get_manual_solve_config()),
+        false => Setup::Game(StartupAction::NewGame(get_manual_config())),
+    }
 }
@@ -0,0 +1,209 @@
+/*
+    Words module.
+
+    The dictionary of the word variant (Jotto played by Mastermind rules):
+    codes and guesses are words of pegs_in_a_line letters from a word list,
+    and each guess gets black pegs for letters in the right place and white
+    pegs for letters elsewhere in the word, counted as for colors.
+
+    Public API:
+    - Dictionary: the words in play, all of one length.
+        - Dictionary::load: the words of a given length in a word list file.
+        - Dictionary::from_words: the same from words already read; an error
+          when none is left.
+        - Dictionary::find: the index of a typed word, or why it can't be
+          played.
+        - Dictionary::index_of / index_of_line: the index of a word given as
+          letters or as a Line of letter pegs.
+        - Dictionary::letters / line / word: a word as letters, as a Line of
+          letter pegs, or as text.
+        - Dictionary::length / len / is_empty: the word length and count.
+
+    Public types:
+    - DictionaryError: reasons a word list can't be played, including a word
+      length outside 1 to MAX_CODE_WIDTH, checked before the file is read.
+    - WordError: reasons a typed word is refused.
+
+    Notes:
+    - Letters are stored as digits 0-25, the palette indices of
+      Color::letters, and a word's index is its Code in a CodeSpace built
+      with the dictionary; the Engine, the bots and the makers play words
+      like any other codes.
+    - A word list has one word per line; blank lines and lines starting with
+      '#' are skipped.
+*/
+
+use crate::types::{Color, Line, MAX_CODE_WIDTH};
+
+use std::fs;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DictionaryError {
+    Unreadable { path: String, reason: String },
+    NoWords { length: usize },
+    // Words must fit in a Code like any other codes.
+    BadLength { length: usize },
+}
+
+impl std::fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DictionaryError::Unreadable { path, reason } => {
+                write!(f, "Could not read the word list '{}': {}.", path, reason)
+            }
+            DictionaryError::NoWords { length } => {
+                write!(f, "The word list has no words of {} letters.", length)
+            }
+            DictionaryError::BadLength { length } => write!(
+                f,
+                "Words of {} letters can't be played; words have 1 to {} letters.",
+                length, MAX_CODE_WIDTH
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordError {
+    WrongLength { expected: usize, found: usize },
+    NotALetter(char),
+    UnknownWord(String),
+}
+
+impl std::fmt::Display for WordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WordError::WrongLength { expected, found } => {
+                write!(f, "Expected {} letters, got {}.", expected, found)
+            }
+            WordError::NotALetter(c) => write!(f, "'{}' is not a letter.", c),
+            WordError::UnknownWord(word) => write!(f, "'{}' is not in the word list.", word),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Dictionary {
+    length: usize,
+    // Letters as digits 0-25, sorted and without duplicates.
+    words: Vec<Vec<u8>>,
+}
+
+impl Dictionary {
+    pub fn load(path: &str, length: usize) -> Result<Self, DictionaryError> {
+        check_length(length)?;
+        let contents = fs::read_to_string(path).map_err(|error| DictionaryError::Unreadable {
+            path: path.to_string(),
+            reason: error.to_string(),
+        })?;
+        let words = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+        Self::from_words(words, length)
+    }
+
+    // Case is ignored; words of another length or with anything but the
+    // letters a-z are left out.
+    pub fn from_words<'a>(
+        words: impl IntoIterator<Item = &'a str>,
+        length: usize,
+    ) -> Result<Self, DictionaryError> {
+        check_length(length)?;
+        let mut letters: Vec<Vec<u8>> = words
+            .into_iter()
+            .filter_map(|word| to_letters(word.trim()).ok())
+            .filter(|word| word.len() == length)
+            .collect();
+        letters.sort_unstable();
+        letters.dedup();
+        if letters.is_empty() {
+            return Err(DictionaryError::NoWords { length });
+        }
+        Ok(Dictionary {
+            length,
+            words: letters,
+        })
+    }
+
+    pub fn find(&self, word: &str) -> Result<usize, WordError> {
+        let word = word.trim();
+        let letters = to_letters(word).map_err(WordError::NotALetter)?;
+        if letters.len() != self.length {
+            return Err(WordError::WrongLength {
+                expected: self.length,
+                found: letters.len(),
+            });
+        }
+        self.index_of(&letters)
+            .ok_or_else(|| WordError::UnknownWord(word.to_lowercase()))
+    }
+
+    pub fn index_of(&self, letters: &[u8]) -> Option<usize> {
+        self.words
+            .binary_search_by(|word| word.as_slice().cmp(letters))
+            .ok()
+    }
+
+    // None if the line isn't a word, or has a peg that isn't a letter.
+    pub fn index_of_line(&self, line: &Line) -> Option<usize> {
+        let letters: Vec<u8> = line
+            .pegs
+            .iter()
+            .map(|peg| match peg.color {
+                Color::Letter(letter) => Some(letter),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        self.index_of(&letters)
+    }
+
+    pub fn letters(&self, index: usize) -> &[u8] {
+        &self.words[index]
+    }
+
+    pub fn line(&self, index: usize) -> Line {
+        Line::new(
+            self.words[index]
+                .iter()
+                .copied()
+                .map(Color::Letter)
+                .collect(),
+        )
+    }
+
+    pub fn word(&self, index: usize) -> String {
+        self.words[index]
+            .iter()
+            .map(|letter| (b'a' + letter) as char)
+            .collect()
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+fn check_length(length: usize) -> Result<(), DictionaryError> {
+    match length {
+        1..=MAX_CODE_WIDTH => Ok(()),
+        _ => Err(DictionaryError::BadLength { length }),
+    }
+}
+
+// The first character that isn't a letter a-z is the error.
+fn to_letters(word: &str) -> Result<Vec<u8>, char> {
+    word.chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ 'a'..='z' => Ok(c as u8 - b'a'),
+            _ => Err(c),
+        })
+        .collect()
+}
@@ -0,0 +1,129 @@
+// The solve assistant must keep exactly the codes that fit the entered
+// answers, recommend a sensible guess, rate any guess by the split it makes,
+// and refuse answers no code could give.
+
+use mastermind::{
+    assistant::{AnswerError, Remaining, SolveAssistant},
+    bot::{BotMode, BotSettings},
+    gamelogic::check_for_matches,
+    strategy::SearchControl,
+    types::Color,
+};
+
+mod common;
+use common::{feedback, line};
+
+fn assistant(pegs: usize, colors: usize, mode: BotMode) -> SolveAssistant {
+    let settings = BotSettings {
+        mode,
+        ..Default::default()
+    };
+    SolveAssistant::new(Color::palette(colors, false), pegs, settings)
+}
+
+#[test]
+fn remaining_codes_fit_every_answer() {
+    let secret = line(&[Color::Green, Color::Red, Color::White]);
+    let mut assistant = assistant(3, 4, BotMode::Classic);
+    let guesses = [
+        line(&[Color::White, Color::White, Color::Black]),
+        line(&[Color::Red, Color::Green, Color::Red]),
+    ];
+    for guess in &guesses {
+        let (_, answer) = check_for_matches(&secret, guess);
+        assistant.add_answer(guess.clone(), answer).unwrap();
+    }
+
+    let Remaining::Codes(codes) = assistant.remaining(64) else {
+        panic!("a 3x4 board is small enough to list");
+    };
+    assert!(codes.contains(&secret));
+    for code in &codes {
+        for guess in &guesses {
+            assert_eq!(
+                check_for_matches(code, guess).1,
+                check_for_matches(&secret, guess).1
+            );
+        }
+    }
+
+    // Above the limit only the count is given.
+    if codes.len() > 1 {
+        assert!(matches!(
+            assistant.remaining(codes.len() - 1),
+            Remaining::Count(count) if count == codes.len() as u64
+        ));
+    }
+}
+
+#[test]
+fn knuth_recommends_1122_on_an_empty_4x6_board() {
+    let assistant = assistant(4, 6, BotMode::Knuth);
+    let guess = assistant.recommend(&SearchControl::unlimited());
+    assert_eq!(
+        guess,
+        line(&[Color::White, Color::White, Color::Black, Color::Black])
+    );
+}
+
+#[test]
+fn evaluation_matches_the_partition() {
+    let mut assistant = assistant(3, 4, BotMode::Classic);
+    assistant
+        .add_answer(
+            line(&[Color::White, Color::Black, Color::Red]),
+            feedback(0, 1),
+        )
+        .unwrap();
+    let guess = line(&[Color::Green, Color::Green, Color::White]);
+    let evaluation = assistant
+        .evaluate(&guess)
+        .expect("small boards can be evaluated");
+
+    let total: u64 = evaluation.parts.iter().map(|(_, count)| count).sum();
+    assert_eq!(total, evaluation.remaining);
+    assert_eq!(evaluation.worst_case, evaluation.parts[0].1);
+    assert!(
+        evaluation
+            .parts
+            .windows(2)
+            .all(|pair| pair[0].1 >= pair[1].1)
+    );
+    // The guess uses White, which the only answer allows, in a new position.
+    assert!(evaluation.is_consistent);
+
+    let ruled_out = line(&[Color::White, Color::Black, Color::Red]);
+    assert!(!assistant.evaluate(&ruled_out).unwrap().is_consistent);
+}
+
+#[test]
+fn bad_answers_are_refused() {
+    let mut assistant = assistant(3, 4, BotMode::Classic);
+    let guess = line(&[Color::White, Color::White, Color::White]);
+    assert_eq!(
+        assistant.add_answer(guess.clone(), feedback(2, 1)),
+        Err(AnswerError::ImpossibleFeedback(feedback(2, 1)))
+    );
+    assert_eq!(
+        assistant.add_answer(line(&[Color::White]), feedback(0, 0)),
+        Err(AnswerError::WrongLength {
+            expected: 3,
+            found: 1
+        })
+    );
+    assert_eq!(
+        assistant.add_answer(line(&[Color::Blue; 3]), feedback(0, 0)),
+        Err(AnswerError::ColorNotInPlay(Color::Blue))
+    );
+
+    assistant.add_answer(guess.clone(), feedback(0, 0)).unwrap();
+    assert_eq!(
+        assistant.add_answer(guess, feedback(1, 0)),
+        Err(AnswerError::Inconsistent {
+            suspects: vec![1, 2]
+        })
+    );
+    assert_eq!(assistant.answers().len(), 1);
+    assert!(assistant.undo().is_some());
+    assert!(assistant.answers().is_empty());
+}
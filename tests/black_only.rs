@@ -0,0 +1,109 @@
+// Under the black-pegs-only rule every answer hides the white pegs, and the
+// bots must still break codes from the black counts alone.
+
+use mastermind::{
+    bot::BotMode,
+    engine::{Engine, ScoringError},
+    gameconfig::{GameConfig, parse_feedback_rule},
+    gamelogic::{feedback_from_flags, is_possible_feedback},
+    gamestate::{Gamestate, RoundStatus},
+    types::{CodeSpace, Color, FeedbackRule, GameMode, Line},
+};
+
+mod common;
+use common::feedback;
+
+fn config(game_mode: GameMode, pegs: u8, colors: u8, mode: BotMode) -> GameConfig {
+    GameConfig {
+        feedback_rule: FeedbackRule::BlackOnly,
+        ..common::config(game_mode, pegs, colors, mode)
+    }
+}
+
+#[test]
+fn guesses_only_get_black_pegs() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 4, 6, BotMode::Classic), None);
+    engine.gamestate.target_line =
+        Line::new(vec![Color::Red, Color::Blue, Color::Green, Color::White]);
+    let guess = Line::new(vec![Color::Red, Color::Green, Color::Blue, Color::Black]);
+    let (flags, answer) = engine.submit_guess(guess).unwrap();
+    assert_eq!(answer, feedback(1, 0));
+    assert_eq!(feedback_from_flags(&flags), feedback(1, 0));
+}
+
+#[test]
+fn white_pegs_are_never_a_possible_answer() {
+    assert!(is_possible_feedback(
+        feedback(2, 1),
+        4,
+        FeedbackRule::Standard
+    ));
+    assert!(!is_possible_feedback(
+        feedback(2, 1),
+        4,
+        FeedbackRule::BlackOnly
+    ));
+    assert!(is_possible_feedback(
+        feedback(3, 0),
+        4,
+        FeedbackRule::BlackOnly
+    ));
+
+    let space =
+        CodeSpace::new(Color::palette(6, false), 4).with_feedback_rule(FeedbackRule::BlackOnly);
+    assert_eq!(space.score(&[0, 1, 2, 3], &[1, 0, 2, 4]), feedback(1, 0));
+
+    let mut engine = Engine::new(
+        &GameConfig {
+            human_scored: true,
+            ..config(GameMode::PlayerVsBot, 3, 4, BotMode::Knuth)
+        },
+        None,
+    );
+    let guess = engine
+        .bot_guess_within(&engine.search_control())
+        .expect("bot has a guess");
+    assert!(matches!(
+        engine.score_bot_guess(guess, feedback(1, 1)),
+        Err(ScoringError::ImpossibleFeedback { .. })
+    ));
+}
+
+#[test]
+fn bots_break_codes_from_black_pegs() {
+    for mode in [
+        BotMode::Classic,
+        BotMode::Knuth,
+        BotMode::Optimal,
+        BotMode::Sampling,
+        BotMode::Constraint,
+    ] {
+        let mut engine = Engine::new(&config(GameMode::SpectateBot, 3, 4, mode), None);
+        while !engine.gamestate.round_over {
+            let (_, answer) = engine.play_bot_turn().expect("bot guess was refused");
+            assert_eq!(answer.correct_color, 0);
+        }
+        assert!(engine.round_status() == RoundStatus::Win, "{} lost", mode);
+    }
+}
+
+#[test]
+fn rule_is_read_from_config_and_saves() {
+    assert_eq!(
+        parse_feedback_rule("black_only"),
+        Some(FeedbackRule::BlackOnly)
+    );
+    assert_eq!(parse_feedback_rule("S"), Some(FeedbackRule::Standard));
+    assert_eq!(parse_feedback_rule("white"), None);
+
+    let engine = Engine::new(&config(GameMode::Practice, 4, 6, BotMode::Classic), None);
+    let saved = serde_json::to_string(&engine.gamestate).unwrap();
+    let loaded: Gamestate = serde_json::from_str(&saved).unwrap();
+    assert_eq!(loaded.feedback_rule, FeedbackRule::BlackOnly);
+
+    // Saves from before the rule existed load as Standard
+    let mut value: serde_json::Value = serde_json::from_str(&saved).unwrap();
+    value.as_object_mut().unwrap().remove("feedback_rule");
+    let old: Gamestate = serde_json::from_value(value).unwrap();
+    assert_eq!(old.feedback_rule, FeedbackRule::Standard);
+}
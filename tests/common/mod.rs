@@ -0,0 +1,54 @@
+// Fixtures shared by the integration tests. Each test file builds on config
+// and overrides only the rules it is about.
+#![allow(dead_code)]
+
+use mastermind::{
+    bot::{BotMode, BotSettings},
+    gameconfig::GameConfig,
+    types::{Color, Feedback, GameMode, Line},
+};
+
+// A twelve-guess game with the bot in the given mode and every other rule
+// left at its default.
+pub fn config(game_mode: GameMode, pegs: u8, colors: u8, mode: BotMode) -> GameConfig {
+    GameConfig {
+        game_mode,
+        number_of_guesses: 12,
+        pegs_in_a_line: pegs,
+        number_of_colors: colors,
+        bot_settings: BotSettings {
+            mode,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub fn line(colors: &[Color]) -> Line {
+    Line::new(colors.to_vec())
+}
+
+pub fn feedback(black: u8, white: u8) -> Feedback {
+    Feedback {
+        correct_position: black,
+        correct_color: white,
+    }
+}
+
+// Every line of the given width over the palette, in palette order.
+pub fn all_codes(palette: &[Color], pegs: usize) -> Vec<Line> {
+    let mut codes = vec![Vec::new()];
+    for _ in 0..pegs {
+        codes = codes
+            .into_iter()
+            .flat_map(|code| {
+                palette.iter().map(move |color| {
+                    let mut longer = code.clone();
+                    longer.push(*color);
+                    longer
+                })
+            })
+            .collect();
+    }
+    codes.into_iter().map(Line::new).collect()
+}
@@ -0,0 +1,57 @@
+// Loading a config file never prints: the settings it had to change come
+// back as warnings for the frontend to show.
+
+use mastermind::{
+    engine::Engine,
+    gameconfig::GameConfig,
+    types::{FeedbackRule, GameMode},
+};
+
+use std::fs;
+
+fn load(name: &str, content: &str) -> (GameConfig, Vec<String>) {
+    let path = std::env::temp_dir().join(format!("mastermind-{}.txt", name));
+    fs::write(&path, content).unwrap();
+    let loaded = GameConfig::load_from_file(path.to_str().unwrap());
+    fs::remove_file(&path).ok();
+    loaded.expect("the file exists")
+}
+
+#[test]
+fn config_warnings_are_returned() {
+    let (cfg, warnings) = load(
+        "warnings",
+        "pegs_in_a_line=9\nfeedback=positional\nno_such_key=1\n",
+    );
+    assert_eq!(cfg.pegs_in_a_line, 9);
+    assert_eq!(cfg.feedback_rule, FeedbackRule::Standard);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("no_such_key"));
+
+    let (_, warnings) = load("clean", "# only a comment\nnumber_of_guesses=8\n");
+    assert!(warnings.is_empty());
+    assert!(GameConfig::load_from_file("no/such/config.txt").is_none());
+}
+
+#[test]
+fn engine_reports_the_rules_it_drops() {
+    let cfg = GameConfig {
+        game_mode: GameMode::Practice,
+        number_of_guesses: 1,
+        static_mode: true,
+        max_lies: 1,
+        ..Default::default()
+    };
+    let engine = Engine::new(&cfg, None);
+    assert!(!engine.gamestate.static_mode);
+    assert_eq!(engine.gamestate.max_lies, 1);
+    assert_eq!(
+        engine.config_warnings,
+        vec!["Static mode needs at least 2 guesses; playing one guess at a time.".to_string()]
+    );
+
+    // A config that was already reconciled has nothing left to change
+    let (settled, warnings) = cfg.reconcile();
+    assert_eq!(warnings.len(), 1);
+    assert!(Engine::new(&settled, None).config_warnings.is_empty());
+}
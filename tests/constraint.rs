@@ -0,0 +1,74 @@
+// The constraint bot must take the bot seat in Constraint mode, play only
+// codes consistent with every clue so far, and keep going on contradictions.
+
+use mastermind::{
+    bot::{BotMode, BotSettings},
+    constraint::ConstraintBot,
+    engine::Engine,
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::RoundStatus,
+    strategy::BreakerStrategy,
+    types::{Color, Feedback, GameMode, Line},
+};
+
+fn spectate_config(pegs: u8, colors: u8, guesses: u8) -> GameConfig {
+    GameConfig {
+        game_mode: GameMode::SpectateBot,
+        number_of_guesses: guesses,
+        pegs_in_a_line: pegs,
+        number_of_colors: colors,
+        is_empty_pegs_allowed: false,
+        bot_settings: BotSettings {
+            mode: BotMode::Constraint,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn play_consistently(cfg: &GameConfig) {
+    let mut engine = Engine::new(cfg, None);
+    let mut history: Vec<(Line, _)> = Vec::new();
+    while engine.round_status() == RoundStatus::Ongoing {
+        let (guess, feedback) = engine.play_bot_turn().expect("the bot plays a legal guess");
+        for (earlier, earlier_feedback) in &history {
+            assert_eq!(score_guess(&guess, earlier), *earlier_feedback);
+        }
+        history.push((guess, feedback));
+    }
+    assert!(engine.round_status() == RoundStatus::Win);
+}
+
+#[test]
+fn constraint_bot_guesses_stay_consistent_and_solve_4x6() {
+    for _ in 0..20 {
+        play_consistently(&spectate_config(4, 6, 12));
+    }
+}
+
+#[test]
+fn constraint_bot_solves_wide_codes() {
+    for _ in 0..3 {
+        play_consistently(&spectate_config(12, 8, 40));
+    }
+}
+
+#[test]
+fn contradictory_clues_still_get_a_guess() {
+    let palette = Color::palette(4, false);
+    let mut bot = ConstraintBot::new(palette.clone(), 6, BotSettings::default());
+    let code = Line::new(vec![palette[0]; 6]);
+    // The same guess can't score both all-correct and nothing.
+    let all_correct = Feedback {
+        correct_position: 6,
+        correct_color: 0,
+    };
+    let nothing = Feedback {
+        correct_position: 0,
+        correct_color: 0,
+    };
+    bot.observe_feedback(&code, all_correct);
+    bot.observe_feedback(&code, nothing);
+    assert_eq!(bot.next_guess().pegs.len(), 6);
+}
@@ -0,0 +1,79 @@
+// Each difficulty level must play the way it promises, and the level must
+// survive a save so the same opponent comes back after loading.
+
+use mastermind::{
+    bot::{BotMode, BotSettings, Difficulty, parse_difficulty},
+    engine::Engine,
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::{Gamestate, RoundStatus},
+    types::{GameMode, Line},
+};
+
+fn config(pegs: u8, colors: u8, difficulty: Difficulty) -> GameConfig {
+    GameConfig {
+        game_mode: GameMode::SpectateBot,
+        number_of_guesses: 12,
+        pegs_in_a_line: pegs,
+        number_of_colors: colors,
+        is_empty_pegs_allowed: false,
+        bot_settings: BotSettings {
+            mode: BotMode::Knuth,
+            difficulty,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn consistent_and_greedy_levels_only_play_fitting_codes() {
+    for difficulty in [Difficulty::Consistent, Difficulty::Greedy] {
+        for _ in 0..10 {
+            let mut engine = Engine::new(&config(3, 4, difficulty), None);
+            let mut history: Vec<(Line, _)> = Vec::new();
+            while engine.round_status() == RoundStatus::Ongoing {
+                let (guess, feedback) =
+                    engine.play_bot_turn().expect("the bot plays a legal guess");
+                for (earlier, earlier_feedback) in &history {
+                    assert_eq!(score_guess(&guess, earlier), *earlier_feedback);
+                }
+                history.push((guess, feedback));
+            }
+        }
+    }
+}
+
+#[test]
+fn random_level_plays_legal_codes_at_any_size() {
+    for pegs in [4, 255] {
+        let mut engine = Engine::new(&config(pegs, 6, Difficulty::Random), None);
+        for _ in 0..3 {
+            let (guess, _) = engine.play_bot_turn().expect("the bot plays a legal guess");
+            assert_eq!(guess.pegs.len(), pegs as usize);
+        }
+    }
+}
+
+#[test]
+fn difficulty_is_kept_in_saves() {
+    let engine = Engine::new(&config(3, 4, Difficulty::Greedy), None);
+    let json = serde_json::to_string(&engine.gamestate).unwrap();
+    let loaded: Gamestate = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.bot_settings.difficulty, Difficulty::Greedy);
+
+    // Saves from before difficulty levels existed get the full-strength bot.
+    let old_json = json.replace(r#","difficulty":"Greedy""#, "");
+    assert_ne!(old_json, json);
+    let old: Gamestate = serde_json::from_str(&old_json).unwrap();
+    assert_eq!(old.bot_settings.difficulty, Difficulty::Full);
+}
+
+#[test]
+fn difficulty_names_parse() {
+    assert_eq!(parse_difficulty("random"), Some(Difficulty::Random));
+    assert_eq!(parse_difficulty("2"), Some(Difficulty::Consistent));
+    assert_eq!(parse_difficulty(" Greedy\n"), Some(Difficulty::Greedy));
+    assert_eq!(parse_difficulty("f"), Some(Difficulty::Full));
+    assert_eq!(parse_difficulty("hard"), None);
+}
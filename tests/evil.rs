@@ -0,0 +1,148 @@
+// The evil maker must answer every guess with the largest remaining
+// partition, stay consistent with everything it said, and still lose to a
+// guess once only that code is left.
+
+use mastermind::{
+    bot::BotMode,
+    engine::Engine,
+    gameconfig::GameConfig,
+    gamelogic::{feedback_from_flags, score_guess},
+    gamestate::{Gamestate, RoundStatus},
+    hints::{Hint, HintKind},
+    types::{Color, GameMode, Line},
+};
+
+mod common;
+use common::all_codes;
+
+fn config(game_mode: GameMode, pegs: u8, colors: u8, evil_maker: bool) -> GameConfig {
+    GameConfig {
+        number_of_guesses: 20,
+        evil_maker,
+        ..common::config(game_mode, pegs, colors, BotMode::Classic)
+    }
+}
+
+// Codes that fit the answers actually given on the board.
+fn consistent_codes(gamestate: &Gamestate, codes: &[Line]) -> Vec<Line> {
+    codes
+        .iter()
+        .filter(|code| {
+            gamestate
+                .guessed_lines
+                .iter()
+                .zip(&gamestate.flag_pegs)
+                .all(|(guess, flags)| score_guess(code, guess) == feedback_from_flags(flags))
+        })
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn evil_answers_keep_the_most_codes() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 4, 6, true), None);
+    let codes = all_codes(&engine.gamestate.palette(), engine.gamestate.pegs_in_a_line);
+
+    // 1122 on 4x6: no answer keeps more than 256 codes (Knuth's worst case).
+    let guess = Line::new(vec![Color::White, Color::White, Color::Black, Color::Black]);
+    engine.submit_guess(guess).unwrap();
+    assert_eq!(consistent_codes(&engine.gamestate, &codes).len(), 256);
+}
+
+#[test]
+fn evil_maker_stays_consistent_until_cornered() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 3, 4, true), None);
+    let codes = all_codes(&engine.gamestate.palette(), engine.gamestate.pegs_in_a_line);
+    // Always guess the first code that still fits; the maker must keep its
+    // target consistent, and the round can only end in a win once one is left.
+    while engine.round_status() == RoundStatus::Ongoing {
+        let remaining = consistent_codes(&engine.gamestate, &codes);
+        assert!(remaining.contains(&engine.gamestate.target_line));
+        let guess = remaining[0].clone();
+        engine.submit_guess(guess).unwrap();
+        if engine.round_status() == RoundStatus::Win {
+            assert_eq!(remaining.len(), 1);
+        }
+    }
+    assert!(engine.round_status() == RoundStatus::Win);
+    assert!(engine.gamestate.guessed_lines.len() > 2);
+}
+
+#[test]
+fn revealed_pegs_bind_the_evil_maker() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 3, 4, true), None);
+    let Some(Hint::RevealPeg { position, color }) = engine.take_hint(HintKind::RevealPeg) else {
+        panic!("a hidden peg is left to reveal");
+    };
+    for colors in [[Color::White; 3], [Color::Black; 3], [Color::Red; 3]] {
+        if engine.gamestate.round_over {
+            break;
+        }
+        engine.submit_guess(Line::new(colors.to_vec())).unwrap();
+        assert_eq!(engine.gamestate.target_line.pegs[position].color, color);
+    }
+}
+
+#[test]
+fn evil_maker_survives_a_save() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 3, 4, true), None);
+    engine
+        .submit_guess(Line::new(vec![Color::White, Color::White, Color::Black]))
+        .unwrap();
+    let json = serde_json::to_string(&engine.gamestate).unwrap();
+    let loaded: Gamestate = serde_json::from_str(&json).unwrap();
+    assert!(loaded.evil_maker);
+
+    // The resumed maker remembers the first answer and answers the next guess
+    // as if it had never been saved.
+    let mut resumed = Engine::from_gamestate(loaded, None);
+    let codes = all_codes(&resumed.gamestate.palette(), 3);
+    resumed
+        .submit_guess(Line::new(vec![Color::Red, Color::Green, Color::Red]))
+        .unwrap();
+    let remaining = consistent_codes(&resumed.gamestate, &codes);
+    assert!(remaining.contains(&resumed.gamestate.target_line));
+}
+
+#[test]
+fn bot_breaker_faces_a_fixed_code() {
+    // In Spectate Bot the option has no effect.
+    let mut engine = Engine::new(&config(GameMode::SpectateBot, 3, 4, true), None);
+    let target = engine.gamestate.target_line.clone();
+    engine.play_bot_turn().expect("the bot plays a legal guess");
+    assert_eq!(engine.gamestate.target_line, target);
+}
+
+#[test]
+fn evil_maker_starts_over_each_round() {
+    let mut engine = Engine::new(&config(GameMode::PlayerVsBot, 3, 4, true), None);
+    let codes = all_codes(&engine.gamestate.palette(), 3);
+    let play_round = |engine: &mut Engine| {
+        while engine.round_status() == RoundStatus::Ongoing {
+            let remaining = consistent_codes(&engine.gamestate, &codes);
+            assert!(remaining.contains(&engine.gamestate.target_line));
+            engine.submit_guess(remaining[0].clone()).unwrap();
+        }
+        engine.finish_round();
+        engine.start_next_round();
+    };
+    play_round(&mut engine);
+
+    // The bot breaks the human's code in between, with no maker in the way
+    let target = Line::new(vec![Color::Red, Color::Green, Color::Red]);
+    engine.set_target(target.clone());
+    while engine.round_status() == RoundStatus::Ongoing {
+        engine.play_bot_turn().unwrap();
+    }
+    assert_eq!(engine.gamestate.target_line, target);
+    engine.finish_round();
+    engine.start_next_round();
+
+    // The maker forgot the first round's answers
+    let guess = Line::new(vec![Color::White, Color::Black, Color::Red]);
+    engine.submit_guess(guess).unwrap();
+    let remaining = consistent_codes(&engine.gamestate, &codes);
+    assert!(remaining.contains(&engine.gamestate.target_line));
+    assert!(remaining.len() > 1);
+    play_round(&mut engine);
+}
@@ -0,0 +1,97 @@
+// A guess that contradicts an earlier clue must be spotted with the right
+// clue, rejected in hard mode for humans only, and never flagged when it fits.
+
+use mastermind::{
+    bot::Difficulty,
+    engine::{Engine, GuessError},
+    gameconfig::GameConfig,
+    gamelogic::{feedback_from_flags, score_guess},
+    types::{Color, Feedback, GameMode, Line},
+};
+
+fn config(game_mode: GameMode, hard_mode: bool) -> GameConfig {
+    GameConfig {
+        game_mode,
+        number_of_guesses: 12,
+        pegs_in_a_line: 4,
+        number_of_colors: 6,
+        is_empty_pegs_allowed: false,
+        hard_mode,
+        ..Default::default()
+    }
+}
+
+// A Practice game with a known target instead of a random one.
+fn practice_with_target(colors: [Color; 4], hard_mode: bool) -> Engine {
+    let mut engine = Engine::new(&config(GameMode::Practice, hard_mode), None);
+    engine.gamestate.target_line = Line::new(colors.to_vec());
+    engine
+}
+
+const TARGET: [Color; 4] = [Color::Red, Color::Red, Color::Blue, Color::Green];
+
+#[test]
+fn flags_read_back_as_the_same_feedback() {
+    let mut engine = practice_with_target(TARGET, false);
+    let guess = Line::new(vec![Color::Red, Color::Blue, Color::White, Color::Red]);
+    let (flags, feedback) = engine.submit_guess(guess).unwrap();
+    assert_eq!(feedback_from_flags(&flags), feedback);
+}
+
+#[test]
+fn contradiction_names_the_clue_it_breaks() {
+    let mut engine = practice_with_target(TARGET, false);
+    engine
+        .submit_guess(Line::new(vec![Color::White; 4]))
+        .unwrap();
+    engine.submit_guess(Line::new(vec![Color::Red; 4])).unwrap();
+
+    // Fits "no white" but has four reds where the second clue said two.
+    let guess = Line::new(vec![Color::Red; 4]);
+    let contradiction = engine.check_guess(&guess).expect("the guess is ruled out");
+    assert_eq!(contradiction.guess_number, 2);
+    assert_eq!(
+        contradiction.clue,
+        Feedback {
+            correct_position: 2,
+            correct_color: 0
+        }
+    );
+    assert_eq!(contradiction.would_score, score_guess(&guess, &guess));
+
+    // The target itself can never contradict the board.
+    assert_eq!(engine.check_guess(&Line::new(TARGET.to_vec())), None);
+}
+
+#[test]
+fn hard_mode_rejects_only_inconsistent_guesses() {
+    let mut engine = practice_with_target(TARGET, true);
+    engine
+        .submit_guess(Line::new(vec![Color::White; 4]))
+        .unwrap();
+
+    let with_white = Line::new(vec![Color::White, Color::Red, Color::Red, Color::Red]);
+    assert!(matches!(
+        engine.submit_guess(with_white),
+        Err(GuessError::Inconsistent(_))
+    ));
+    assert_eq!(engine.gamestate.guessed_lines.len(), 1);
+
+    let fitting = Line::new(vec![Color::Red, Color::Red, Color::Red, Color::Red]);
+    assert!(engine.submit_guess(fitting).is_ok());
+}
+
+#[test]
+fn hard_mode_leaves_bot_guesses_alone() {
+    let mut cfg = config(GameMode::SpectateBot, true);
+    cfg.bot_settings.difficulty = Difficulty::Random;
+    let mut engine = Engine::new(&cfg, None);
+    for _ in 0..5 {
+        if engine.gamestate.round_over {
+            break;
+        }
+        engine
+            .play_bot_turn()
+            .expect("a random bot is still allowed to guess");
+    }
+}
@@ -0,0 +1,176 @@
+// Hints must tell the truth about the board, show each revealed peg once, be
+// counted in the round's score, and only be given to a human breaker.
+
+use mastermind::{
+    bot::BotMode,
+    engine::Engine,
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::Gamestate,
+    hints::{Hint, HintKind, parse_hint_kind},
+    types::{Color, GameMode, Line},
+};
+
+mod common;
+use common::all_codes;
+
+use std::collections::HashSet;
+
+fn config(game_mode: GameMode) -> GameConfig {
+    common::config(game_mode, 3, 4, BotMode::Classic)
+}
+
+fn brute_force_count(gamestate: &Gamestate) -> u64 {
+    all_codes(&gamestate.palette(), gamestate.pegs_in_a_line)
+        .iter()
+        .filter(|code| {
+            gamestate
+                .guessed_lines
+                .iter()
+                .all(|guess| score_guess(code, guess) == score_guess(&gamestate.target_line, guess))
+        })
+        .count() as u64
+}
+
+#[test]
+fn count_matches_brute_force_as_guesses_come_in() {
+    let mut engine = Engine::new(&config(GameMode::Practice), None);
+    let palette = engine.gamestate.palette();
+    assert_eq!(
+        engine.take_hint(HintKind::Count),
+        Some(Hint::Count(Some(64)))
+    );
+    for color in &palette[..3] {
+        engine.submit_guess(Line::new(vec![*color; 3])).unwrap();
+        if engine.gamestate.round_over {
+            break;
+        }
+        let expected = brute_force_count(&engine.gamestate);
+        assert_eq!(
+            engine.take_hint(HintKind::Count),
+            Some(Hint::Count(Some(expected)))
+        );
+    }
+}
+
+#[test]
+fn suggestion_fits_the_board() {
+    let mut engine = Engine::new(&config(GameMode::Practice), None);
+    let palette = engine.gamestate.palette();
+    engine
+        .submit_guess(Line::new(vec![palette[0], palette[1], palette[1]]))
+        .unwrap();
+    if engine.gamestate.round_over {
+        return;
+    }
+    let Some(Hint::Suggestion(line)) = engine.take_hint(HintKind::Suggestion) else {
+        panic!("a suggestion hint gives a line");
+    };
+    for guess in &engine.gamestate.guessed_lines {
+        assert_eq!(
+            score_guess(&line, guess),
+            score_guess(&engine.gamestate.target_line, guess)
+        );
+    }
+}
+
+#[test]
+fn reveals_show_every_peg_once() {
+    let mut engine = Engine::new(&config(GameMode::Practice), None);
+    let mut seen = HashSet::new();
+    for _ in 0..3 {
+        let Some(Hint::RevealPeg { position, color }) = engine.take_hint(HintKind::RevealPeg)
+        else {
+            panic!("a hidden peg is left to reveal");
+        };
+        assert!(seen.insert(position));
+        assert_eq!(engine.gamestate.target_line.pegs[position].color, color);
+    }
+    assert_eq!(engine.take_hint(HintKind::RevealPeg), None);
+}
+
+#[test]
+fn hints_are_charged_to_the_breaker() {
+    let mut engine = Engine::new(&config(GameMode::PlayerVsBot), None);
+    // Let the bot break its round first if it opens the game.
+    if engine.is_bots_turn() {
+        let palette = engine.gamestate.palette();
+        engine.set_target(Line::new(vec![palette[0]; 3]));
+        while !engine.gamestate.round_over {
+            engine.play_bot_turn().expect("the bot plays a legal guess");
+        }
+        engine.finish_round();
+        engine.start_next_round();
+    }
+    engine.take_hint(HintKind::Count);
+    engine.take_hint(HintKind::RevealPeg);
+    assert_eq!(engine.gamestate.hints_used, 2);
+
+    let target = engine.gamestate.target_line.clone();
+    engine.submit_guess(target).unwrap();
+    let result = engine.finish_round();
+    assert_eq!(result.hints_used, 2);
+    assert_eq!(result.hint_penalty, 2);
+    assert_eq!(result.score_delta, 1 + 2);
+
+    engine.start_next_round();
+    assert_eq!(engine.gamestate.hints_used, 0);
+    assert!(engine.gamestate.revealed_pegs.is_empty());
+}
+
+#[test]
+fn hint_penalties_never_overflow_the_scores() {
+    let mut engine = Engine::new(
+        &GameConfig {
+            hint_penalty: 255,
+            ..config(GameMode::TwoPlayer)
+        },
+        None,
+    );
+    let target = Line::new(vec![Color::Red, Color::Green, Color::White]);
+    // P1 breaks rounds 1 and 3 with a hint each; P2 makes both codes.
+    for round in 1..=3 {
+        engine.set_target(target.clone());
+        if round != 2 {
+            engine.take_hint(HintKind::Count).unwrap();
+        }
+        engine.submit_guess(target.clone()).unwrap();
+        engine.finish_round();
+        engine.start_next_round();
+    }
+    assert_eq!(engine.gamestate.p2_score, 255);
+    assert_eq!(engine.gamestate.p1_score, 1);
+}
+
+#[test]
+fn no_hints_for_the_bot_or_after_the_round() {
+    let mut engine = Engine::new(&config(GameMode::SpectateBot), None);
+    assert_eq!(engine.take_hint(HintKind::Count), None);
+
+    let mut engine = Engine::new(&config(GameMode::Practice), None);
+    let target = engine.gamestate.target_line.clone();
+    engine.submit_guess(target).unwrap();
+    assert_eq!(engine.take_hint(HintKind::Suggestion), None);
+    assert_eq!(engine.gamestate.hints_used, 0);
+}
+
+#[test]
+fn old_saves_load_with_the_default_penalty() {
+    let engine = Engine::new(&config(GameMode::Practice), None);
+    let json = serde_json::to_value(&engine.gamestate).unwrap();
+    let mut old = json.as_object().unwrap().clone();
+    for field in ["hint_penalty", "hints_used", "revealed_pegs"] {
+        assert!(old.remove(field).is_some());
+    }
+    let loaded: Gamestate = serde_json::from_value(old.into()).unwrap();
+    assert_eq!(loaded.hint_penalty, 1);
+    assert_eq!(loaded.hints_used, 0);
+}
+
+#[test]
+fn hint_names_parse() {
+    assert_eq!(parse_hint_kind(" count"), Some(HintKind::Count));
+    assert_eq!(parse_hint_kind("s"), Some(HintKind::Suggestion));
+    assert_eq!(parse_hint_kind("Reveal\n"), Some(HintKind::RevealPeg));
+    assert_eq!(parse_hint_kind(""), None);
+}
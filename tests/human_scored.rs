@@ -0,0 +1,130 @@
+// In a human-scored game the bot must solve a code the engine never sees from
+// typed-in counts alone, and impossible or contradictory answers must be
+// refused with the answer that has to be wrong.
+
+use mastermind::{
+    bot::{BotMode, BotSettings},
+    engine::{Engine, ScoringError},
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::RoundStatus,
+    types::{Color, GameMode, Line},
+};
+
+mod common;
+use common::feedback;
+
+// Player vs Bot with the bot breaking first: player 1 makes the code. Classic
+// mode only ever plays codes that fit the answers after its opening.
+fn human_scored_engine() -> Engine {
+    let cfg = GameConfig {
+        game_mode: GameMode::PlayerVsBot,
+        number_of_guesses: 12,
+        pegs_in_a_line: 3,
+        number_of_colors: 4,
+        is_empty_pegs_allowed: false,
+        bot_settings: BotSettings {
+            mode: BotMode::Classic,
+            ..Default::default()
+        },
+        human_scored: true,
+        ..Default::default()
+    };
+    let mut engine = Engine::new(&cfg, None);
+    if !engine.is_bots_turn() {
+        engine.gamestate.p1s_turn = false;
+    }
+    assert!(engine.is_human_scoring());
+    assert!(!engine.needs_human_target());
+    engine
+}
+
+#[test]
+fn bot_solves_a_code_it_never_sees() {
+    let secret = Line::new(vec![Color::Green, Color::White, Color::Green]);
+    let mut engine = human_scored_engine();
+    let control = engine.search_control();
+    while engine.round_status() == RoundStatus::Ongoing {
+        assert_ne!(engine.gamestate.target_line, secret);
+        let guess = engine
+            .bot_guess_within(&control)
+            .expect("the bot has a guess");
+        let answer = score_guess(&secret, &guess);
+        engine.score_bot_guess(guess, answer).unwrap();
+    }
+    assert!(engine.round_status() == RoundStatus::Win);
+    assert_eq!(engine.gamestate.target_line, secret);
+}
+
+#[test]
+fn impossible_counts_are_refused() {
+    let mut engine = human_scored_engine();
+    let guess = Line::new(vec![Color::White, Color::Black, Color::Red]);
+    for answer in [feedback(2, 1), feedback(2, 2), feedback(4, 0)] {
+        assert_eq!(
+            engine.score_bot_guess(guess.clone(), answer),
+            Err(ScoringError::ImpossibleFeedback {
+                feedback: answer,
+                pegs: 3
+            })
+        );
+    }
+    assert!(engine.gamestate.guessed_lines.is_empty());
+}
+
+#[test]
+fn contradictions_name_the_suspect_answers() {
+    let mut engine = human_scored_engine();
+    // The code is White White White, but the first answer forgets the whites.
+    let first = Line::new(vec![Color::White, Color::White, Color::Black]);
+    let second = Line::new(vec![Color::Red, Color::Black, Color::Red]);
+    let third = Line::new(vec![Color::White; 3]);
+    engine.score_bot_guess(first, feedback(0, 0)).unwrap();
+    engine.score_bot_guess(second, feedback(0, 0)).unwrap();
+
+    // Only the first or the third answer can be wrong; the second one fits
+    // either way.
+    assert_eq!(
+        engine.score_bot_guess(third.clone(), feedback(3, 0)),
+        Err(ScoringError::Inconsistent {
+            suspects: vec![1, 3]
+        })
+    );
+    assert_eq!(engine.gamestate.guessed_lines.len(), 2);
+
+    engine.correct_answer(1, feedback(2, 0)).unwrap();
+    engine.score_bot_guess(third, feedback(3, 0)).unwrap();
+    assert!(engine.round_status() == RoundStatus::Win);
+}
+
+#[test]
+fn corrected_answers_retrain_the_bot() {
+    let secret = Line::new(vec![Color::Red, Color::Red, Color::Black]);
+    let mut engine = human_scored_engine();
+    let control = engine.search_control();
+
+    let guess = engine.bot_guess_within(&control).unwrap();
+    let truth = score_guess(&secret, &guess);
+    // Any other possible answer will do as the mistake.
+    let mistake = if truth == feedback(0, 0) {
+        feedback(0, 1)
+    } else {
+        feedback(0, 0)
+    };
+    engine.score_bot_guess(guess.clone(), mistake).unwrap();
+    engine.correct_answer(1, truth).unwrap();
+    assert_eq!(
+        engine.correct_answer(2, truth),
+        Err(ScoringError::NoSuchGuess(2))
+    );
+
+    let next = engine.bot_guess_within(&control).unwrap();
+    assert_eq!(score_guess(&next, &guess), truth);
+}
+
+#[test]
+fn bot_turns_wait_for_the_human() {
+    let mut engine = human_scored_engine();
+    assert_eq!(engine.play_bot_turn(), None);
+    assert!(engine.gamestate.guessed_lines.is_empty());
+}
@@ -0,0 +1,65 @@
+// Knuth mode must reproduce the classic results on 4 pegs x 6 colors.
+
+use mastermind::{
+    bot::{Bot, BotMode, BotSettings},
+    gamelogic::score_guess,
+    types::{Color, DEFAULT_COLORS, Line},
+};
+
+fn knuth_bot() -> Bot {
+    let settings = BotSettings {
+        mode: BotMode::Knuth,
+        ..Default::default()
+    };
+    Bot::new(Color::palette(DEFAULT_COLORS, false), 4, settings)
+}
+
+// Plays the bot against every secret in `secrets` at once, following each
+// feedback branch, and returns the most guesses any secret needed.
+fn worst_case_guesses(bot: &Bot, secrets: &[Line], guesses_so_far: usize) -> usize {
+    let mut bot = bot.clone();
+    let guess = bot.next_guess();
+    let mut worst = guesses_so_far + 1;
+
+    let mut branches: Vec<(_, Vec<Line>)> = Vec::new();
+    for secret in secrets {
+        if *secret == guess {
+            continue;
+        }
+        let feedback = score_guess(secret, &guess);
+        match branches.iter_mut().find(|(f, _)| *f == feedback) {
+            Some((_, branch)) => branch.push(secret.clone()),
+            None => branches.push((feedback, vec![secret.clone()])),
+        }
+    }
+
+    for (feedback, branch) in branches {
+        let mut next = bot.clone();
+        next.observe_feedback(&guess, feedback);
+        worst = worst.max(worst_case_guesses(&next, &branch, guesses_so_far + 1));
+    }
+    worst
+}
+
+#[test]
+fn knuth_opens_with_1122() {
+    let mut bot = knuth_bot();
+    let opening = bot.next_guess();
+    assert_eq!(
+        opening,
+        Line::new(vec![Color::White, Color::White, Color::Black, Color::Black])
+    );
+}
+
+#[test]
+fn knuth_solves_every_4x6_code_in_five_guesses() {
+    let bot = knuth_bot();
+    let secrets: Vec<Line> = bot
+        .possible_solutions
+        .iter()
+        .map(|code| bot.space.unrank(*code))
+        .collect();
+    assert_eq!(secrets.len(), 1296);
+
+    assert!(worst_case_guesses(&bot, &secrets, 0) <= 5);
+}
@@ -0,0 +1,246 @@
+// With max_lies set the Code Maker may give up to that many false answers a
+// round: a bot Maker lies adversarially, a human scoring by hand declares
+// the lies, and the breaker bot keeps every code within the lie budget.
+
+use mastermind::{
+    bot::{Bot, BotMode, BotSettings, Heuristic},
+    engine::{Engine, ScoringError},
+    gameconfig::GameConfig,
+    gamelogic::{check_under_rule, score_guess},
+    gamestate::RoundStatus,
+    hints::{Hint, HintKind},
+    lies::find_lies,
+    types::{CodeSpace, Color, Feedback, GameMode, Line},
+};
+
+mod common;
+use common::{feedback, line};
+
+fn config(game_mode: GameMode, pegs: u8, colors: u8, mode: BotMode) -> GameConfig {
+    GameConfig {
+        max_lies: 1,
+        ..common::config(game_mode, pegs, colors, mode)
+    }
+}
+
+// Every answer but the lies found at the end is what the target gives.
+fn assert_lies_within_budget(engine: &Engine) {
+    let gamestate = &engine.gamestate;
+    let lies = find_lies(gamestate);
+    assert!(lies.len() <= gamestate.max_lies as usize);
+    for (i, (guess, flags)) in gamestate
+        .guessed_lines
+        .iter()
+        .zip(&gamestate.flag_pegs)
+        .enumerate()
+    {
+        let (truth, _) = check_under_rule(&gamestate.target_line, guess, gamestate.feedback_rule);
+        assert_eq!(truth != *flags, lies.contains(&i));
+    }
+}
+
+#[test]
+fn bot_keeps_codes_within_the_lie_budget() {
+    let space = CodeSpace::new(Color::palette(4, false), 3).with_max_lies(1);
+    let target = line(&[Color::Red, Color::Green, Color::White]);
+    let guess = line(&[Color::Red, Color::White, Color::White]);
+    let mut bot = Bot::with_space(space.clone(), BotSettings::default());
+
+    // A false answer only costs the target one lie
+    bot.observe_feedback(&guess, feedback(0, 0));
+    let code = space.rank(&target);
+    let index = bot.possible_solutions.binary_search(&code).unwrap();
+    assert_eq!(bot.lie_counts[index], 1);
+    // The guess itself is gone: a win is never hidden
+    assert!(
+        bot.possible_solutions
+            .binary_search(&space.rank(&guess))
+            .is_err()
+    );
+
+    // A second false answer is one too many
+    bot.observe_feedback(&guess, feedback(0, 0));
+    assert!(bot.possible_solutions.binary_search(&code).is_err());
+    assert!(bot.lie_counts.iter().all(|lies| *lies <= 1));
+    assert_eq!(bot.lie_counts.len(), bot.possible_solutions.len());
+}
+
+#[test]
+fn bots_break_codes_despite_lies() {
+    for mode in [BotMode::Classic, BotMode::Knuth, BotMode::Optimal] {
+        let mut engine = Engine::new(&config(GameMode::SpectateBot, 3, 4, mode), None);
+        while !engine.gamestate.round_over {
+            engine.play_bot_turn().expect("bot guess was refused");
+        }
+        assert!(engine.round_status() == RoundStatus::Win, "{} lost", mode);
+        assert_lies_within_budget(&engine);
+    }
+}
+
+// Every code with its lie left survives any answer, so guesses differ in
+// how they split the codes that have used theirs.
+#[test]
+fn heuristics_still_prefer_splitting_guesses() {
+    let space = CodeSpace::new(Color::palette(6, false), 4).with_max_lies(1);
+    let parts = |bot: &Bot, guess: &Line| {
+        let mut answers: Vec<Feedback> = bot
+            .possible_solutions
+            .iter()
+            .map(|code| score_guess(&space.unrank(*code), guess))
+            .collect();
+        answers.sort_by_key(|feedback| (feedback.correct_position, feedback.correct_color));
+        answers.dedup();
+        answers.len()
+    };
+    let opening = line(&[Color::Red, Color::Red, Color::Green, Color::Green]);
+    let one_color = line(&[Color::Red; 4]);
+    for heuristic in [Heuristic::MostParts, Heuristic::ExpectedSize] {
+        let settings = BotSettings {
+            mode: BotMode::Knuth,
+            heuristic,
+            ..Default::default()
+        };
+        let mut bot = Bot::with_space(space.clone(), settings);
+        bot.observe_feedback(&opening, feedback(0, 0));
+        let guess = bot.next_guess();
+        assert!(
+            parts(&bot, &guess) > parts(&bot, &one_color),
+            "{} guessed {}",
+            heuristic,
+            guess
+        );
+    }
+}
+
+#[test]
+fn bot_maker_lies_at_most_k_times() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 3, 4, BotMode::Classic), None);
+    let target = engine.gamestate.target_line.clone();
+    for colors in [
+        [Color::Red, Color::Red, Color::Red],
+        [Color::Green, Color::Green, Color::Black],
+        [Color::White, Color::Black, Color::Red],
+        [Color::Black, Color::White, Color::Green],
+    ] {
+        if line(&colors) != target {
+            engine.submit_guess(line(&colors)).unwrap();
+        }
+    }
+    assert_eq!(engine.gamestate.target_line, target);
+    assert_lies_within_budget(&engine);
+
+    // The code itself always wins
+    engine.submit_guess(target).unwrap();
+    assert!(engine.round_status() == RoundStatus::Win);
+
+    // An evil maker settles on a code the answers lie about at most once
+    let mut engine = Engine::new(
+        &GameConfig {
+            evil_maker: true,
+            ..config(GameMode::Practice, 3, 4, BotMode::Classic)
+        },
+        None,
+    );
+    for colors in [
+        [Color::Red, Color::Red, Color::Green],
+        [Color::Black, Color::White, Color::Red],
+        [Color::Green, Color::Black, Color::Black],
+    ] {
+        engine.submit_guess(line(&colors)).unwrap();
+    }
+    assert_lies_within_budget(&engine);
+}
+
+#[test]
+fn human_maker_declares_lies() {
+    let mut engine = Engine::new(
+        &GameConfig {
+            human_scored: true,
+            ..config(GameMode::PlayerVsBot, 3, 4, BotMode::Knuth)
+        },
+        None,
+    );
+    engine.gamestate.p1s_turn = false;
+    // The human's code is Red Green White
+    let target = line(&[Color::Red, Color::Green, Color::White]);
+    let control = engine.search_control();
+
+    let guess = engine.bot_guess_within(&control).unwrap();
+    assert!(engine.can_declare_lie());
+    assert_eq!(
+        engine.declare_lie(guess.clone(), feedback(3, 0)),
+        Err(ScoringError::WinningLie)
+    );
+    let truth = score_guess(&target, &guess);
+    let lie = if truth == feedback(0, 0) {
+        feedback(1, 0)
+    } else {
+        feedback(0, 0)
+    };
+    engine.declare_lie(guess, lie).unwrap();
+    assert!(!engine.can_declare_lie());
+
+    let guess = engine.bot_guess_within(&control).unwrap();
+    assert_eq!(
+        engine.declare_lie(guess.clone(), feedback(0, 0)),
+        Err(ScoringError::NoLiesLeft { max: 1 })
+    );
+    while !engine.gamestate.round_over {
+        let guess = engine.bot_guess_within(&control).unwrap();
+        engine
+            .score_bot_guess(guess.clone(), score_guess(&target, &guess))
+            .expect("true answers fit apart from the declared lie");
+    }
+    assert!(engine.round_status() == RoundStatus::Win);
+    assert_eq!(find_lies(&engine.gamestate), vec![0]);
+}
+
+#[test]
+fn clue_checks_allow_the_lies() {
+    let mut engine = Engine::new(
+        &GameConfig {
+            max_lies: 0,
+            ..config(GameMode::Practice, 3, 4, BotMode::Classic)
+        },
+        None,
+    );
+    engine.gamestate.target_line = line(&[Color::Red, Color::Green, Color::White]);
+    engine
+        .submit_guess(line(&[Color::Red, Color::Red, Color::Black]))
+        .unwrap();
+    let honest_count = match engine.take_hint(HintKind::Count) {
+        Some(Hint::Count(Some(count))) => count,
+        _ => panic!("a 3x4 board can be counted"),
+    };
+    let other = line(&[Color::Green, Color::Green, Color::Green]);
+    assert!(engine.check_guess(&other).is_some());
+
+    // One contradicted clue could be the lie
+    engine.gamestate.max_lies = 1;
+    assert!(engine.check_guess(&other).is_none());
+    let Some(Hint::Count(Some(count))) = engine.take_hint(HintKind::Count) else {
+        panic!("a 3x4 board can be counted");
+    };
+    // Every code but the guess itself, which would have won
+    assert!(count > honest_count);
+    assert_eq!(count, 63);
+}
+
+#[test]
+fn lies_need_a_small_dynamic_game() {
+    let engine = Engine::new(
+        &GameConfig {
+            static_mode: true,
+            ..config(GameMode::Practice, 3, 4, BotMode::Classic)
+        },
+        None,
+    );
+    assert_eq!(engine.gamestate.max_lies, 0);
+
+    let engine = Engine::new(&config(GameMode::Practice, 12, 8, BotMode::Classic), None);
+    assert_eq!(engine.gamestate.max_lies, 0);
+
+    let engine = Engine::new(&config(GameMode::Practice, 4, 6, BotMode::Classic), None);
+    assert_eq!(engine.gamestate.max_lies, 1);
+    assert_eq!(engine.gamestate.code_space().max_lies, 1);
+}
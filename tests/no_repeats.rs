@@ -0,0 +1,95 @@
+// Without repeated colors (Bulls and Cows) targets, accepted guesses and every
+// bot's guesses must use each color at most once.
+
+use mastermind::{
+    bot::BotMode,
+    engine::{Engine, GuessError},
+    gameconfig::{GameConfig, fits_without_repeats},
+    gamelogic::find_repeated_color,
+    gamestate::RoundStatus,
+    types::{CodeSpace, Color, GameMode, Line},
+};
+
+mod common;
+
+fn config(game_mode: GameMode, pegs: u8, colors: u8, mode: BotMode) -> GameConfig {
+    GameConfig {
+        no_repeats: true,
+        ..common::config(game_mode, pegs, colors, mode)
+    }
+}
+
+#[test]
+fn code_space_lists_only_codes_without_repeats() {
+    let space = CodeSpace::new(Color::palette(10, false), 4).with_no_repeats(true);
+    assert_eq!(space.count(), Some(5040));
+
+    let codes = space.codes();
+    assert_eq!(codes.len(), 5040);
+    assert!(codes.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(
+        codes
+            .iter()
+            .all(|code| find_repeated_color(&space.unrank(*code)).is_none())
+    );
+
+    let too_wide = CodeSpace::new(Color::palette(3, false), 4).with_no_repeats(true);
+    assert_eq!(too_wide.count(), Some(0));
+}
+
+#[test]
+fn pegs_may_not_outnumber_the_symbols() {
+    assert!(fits_without_repeats(4, 4, false));
+    assert!(fits_without_repeats(5, 4, true));
+    assert!(!fits_without_repeats(5, 4, false));
+
+    let engine = Engine::new(&config(GameMode::Practice, 5, 4, BotMode::Classic), None);
+    assert!(!engine.gamestate.no_repeats);
+}
+
+#[test]
+fn targets_never_repeat_a_color() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 4, 10, BotMode::Classic), None);
+    for _ in 0..20 {
+        assert_eq!(find_repeated_color(&engine.gamestate.target_line), None);
+        engine.start_next_round();
+    }
+}
+
+#[test]
+fn guesses_repeating_a_color_are_refused() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 4, 10, BotMode::Classic), None);
+    let guess = Line::new(vec![Color::Red, Color::Blue, Color::Red, Color::Green]);
+    assert_eq!(
+        engine.submit_guess(guess),
+        Err(GuessError::RepeatedColor(Color::Red))
+    );
+    assert!(engine.gamestate.guessed_lines.is_empty());
+
+    let guess = Line::new(vec![Color::Red, Color::Blue, Color::White, Color::Green]);
+    assert!(engine.submit_guess(guess).is_ok());
+}
+
+// Plays a Spectate Bot round to the end; every guess must be accepted.
+fn play_round(mode: BotMode, pegs: u8, colors: u8) -> Engine {
+    let mut engine = Engine::new(&config(GameMode::SpectateBot, pegs, colors, mode), None);
+    while !engine.gamestate.round_over {
+        let (guess, _) = engine.play_bot_turn().expect("bot guess was refused");
+        assert_eq!(find_repeated_color(&guess), None);
+    }
+    engine
+}
+
+#[test]
+fn enumerating_bots_break_codes_without_repeats() {
+    for mode in [BotMode::Classic, BotMode::Knuth, BotMode::Optimal] {
+        let engine = play_round(mode, 3, 6);
+        assert!(engine.round_status() == RoundStatus::Win, "{} lost", mode);
+    }
+}
+
+#[test]
+fn sampling_bot_guesses_without_repeats() {
+    play_round(BotMode::Sampling, 4, 8);
+    play_round(BotMode::Constraint, 4, 8);
+}
@@ -0,0 +1,152 @@
+// The optimal solver must match a plain exhaustive search on tiny games, and
+// the Optimal bot must actually play the strategy it finds.
+
+use mastermind::{
+    bot::{Bot, BotMode, BotSettings},
+    feedbacktable::FeedbackTable,
+    gamelogic::{score_digits, score_guess},
+    optimal::OptimalSolver,
+    strategy::SearchControl,
+    symmetry::Symmetries,
+    types::{Code, CodeSpace, Color, Feedback},
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn score(space: &CodeSpace, target: Code, guess: Code) -> Feedback {
+    let mut target_digits = vec![0; space.width];
+    let mut guess_digits = vec![0; space.width];
+    space.digits(target, &mut target_digits);
+    space.digits(guess, &mut guess_digits);
+    score_digits(&target_digits, &guess_digits)
+}
+
+fn solver(pegs: usize, colors: usize) -> (CodeSpace, OptimalSolver) {
+    let space = CodeSpace::new(Color::palette(colors, false), pegs);
+    let scoring_space = space.clone();
+    let table = FeedbackTable::build(&space, move |target, guess| {
+        score(&scoring_space, target, guess)
+    })
+    .expect("tiny spaces get a table");
+    let solver = OptimalSolver::new(space.clone(), Arc::new(table)).expect("space is small enough");
+    (space, solver)
+}
+
+fn all_codes(space: &CodeSpace) -> Vec<Code> {
+    (0..space.size().unwrap()).collect()
+}
+
+// Minimum total guesses by trying every guess at every step, no pruning.
+fn brute_force_total(
+    space: &CodeSpace,
+    candidates: &[Code],
+    memo: &mut HashMap<Vec<Code>, u32>,
+) -> u32 {
+    if candidates.len() <= 1 {
+        return candidates.len() as u32;
+    }
+    if let Some(total) = memo.get(candidates) {
+        return *total;
+    }
+
+    let mut best = u32::MAX;
+    for guess in all_codes(space) {
+        let mut classes: HashMap<Feedback, Vec<Code>> = HashMap::new();
+        for candidate in candidates {
+            if *candidate != guess {
+                classes
+                    .entry(score(space, *candidate, guess))
+                    .or_default()
+                    .push(*candidate);
+            }
+        }
+        // A guess that learns nothing can never be part of an optimal strategy.
+        if classes.len() == 1 && classes.values().next().unwrap().len() == candidates.len() {
+            continue;
+        }
+        let total = candidates.len() as u32
+            + classes
+                .values()
+                .map(|class| brute_force_total(space, class, memo))
+                .sum::<u32>();
+        best = best.min(total);
+    }
+    memo.insert(candidates.to_vec(), best);
+    best
+}
+
+#[test]
+fn optimal_matches_brute_force_on_tiny_games() {
+    for (pegs, colors) in [(2, 2), (2, 3), (3, 2), (2, 4), (3, 3)] {
+        let (space, mut solver) = solver(pegs, colors);
+        let codes = all_codes(&space);
+        let expected = brute_force_total(&space, &codes, &mut HashMap::new());
+        assert_eq!(
+            solver.total_guesses(&codes, &Symmetries::new(&space)),
+            expected,
+            "{} pegs x {} colors",
+            pegs,
+            colors
+        );
+    }
+}
+
+#[test]
+fn optimal_bot_plays_the_optimal_strategy() {
+    let (space, mut solver) = solver(3, 4);
+    let codes = all_codes(&space);
+    let optimum = solver.total_guesses(&codes, &Symmetries::new(&space));
+
+    let settings = BotSettings {
+        mode: BotMode::Optimal,
+        ..Default::default()
+    };
+    let bot = Bot::new(Color::palette(4, false), 3, settings);
+    let mut total = 0;
+    for secret in &codes {
+        let secret = space.unrank(*secret);
+        let mut bot = bot.clone();
+        loop {
+            total += 1;
+            let guess = bot.next_guess();
+            if guess == secret {
+                break;
+            }
+            bot.observe_feedback(&guess, score_guess(&secret, &guess));
+        }
+    }
+    assert_eq!(total, optimum);
+}
+
+#[test]
+fn stopped_searches_leave_the_memo_exact() {
+    let (space, _) = solver(3, 5);
+    let codes = all_codes(&space);
+    let expected = solver(3, 5)
+        .1
+        .total_guesses(&codes, &Symmetries::new(&space));
+
+    for limit_ms in [0, 1, 5] {
+        let (space, mut solver) = solver(3, 5);
+        let control = SearchControl::new(Some(Duration::from_millis(limit_ms)));
+        if limit_ms == 0 {
+            control.cancel();
+        }
+        let guess = solver.best_guess_within(&codes, &Symmetries::new(&space), &control);
+        assert!(limit_ms > 0 || guess.is_none());
+        assert_eq!(
+            solver.total_guesses(&codes, &Symmetries::new(&space)),
+            expected
+        );
+    }
+}
+
+// Takes a few seconds in release mode: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn optimal_4x6_averages_4_3403_guesses() {
+    let (space, mut solver) = solver(4, 6);
+    let codes = all_codes(&space);
+    assert_eq!(solver.total_guesses(&codes, &Symmetries::new(&space)), 5625);
+}
@@ -0,0 +1,159 @@
+// Under the positional rule every peg of a guess gets its own mark, the
+// board's flags stand under the pegs they mark, and the bot prunes and plans
+// with the marks rather than just their counts.
+
+use mastermind::{
+    bot::{Bot, BotMode, BotSettings},
+    engine::Engine,
+    gameconfig::{GameConfig, parse_feedback_rule},
+    gamelogic::{feedback_from_flags, mark_positions, score_guess},
+    gamestate::RoundStatus,
+    hints::{Hint, HintKind},
+    types::{CodeSpace, Color, FeedbackRule, GameMode},
+};
+
+mod common;
+use common::line;
+
+use Color::{Black as B, Empty as E, White as W};
+
+fn config(game_mode: GameMode, pegs: u8, colors: u8, mode: BotMode) -> GameConfig {
+    GameConfig {
+        feedback_rule: FeedbackRule::Positional,
+        ..common::config(game_mode, pegs, colors, mode)
+    }
+}
+
+#[test]
+fn each_peg_is_marked_in_place() {
+    let target = line(&[Color::Red, Color::Red, Color::Green, Color::Blue]);
+    let guess = line(&[Color::Red, Color::Green, Color::Red, Color::Yellow]);
+    assert_eq!(mark_positions(&target, &guess), line(&[B, W, W, E]));
+
+    // A color is only marked present as often as the code still has it
+    let target = line(&[Color::Red, Color::Blue, Color::Blue, Color::Blue]);
+    let guess = line(&[Color::Red, Color::Red, Color::Red, Color::Blue]);
+    let marks = mark_positions(&target, &guess);
+    assert_eq!(marks, line(&[B, E, E, B]));
+    assert_eq!(feedback_from_flags(&marks), score_guess(&target, &guess));
+}
+
+#[test]
+fn the_board_keeps_the_marks() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 4, 6, BotMode::Classic), None);
+    engine.gamestate.target_line = line(&[Color::Red, Color::Blue, Color::Green, Color::White]);
+    let guess = line(&[Color::Blue, Color::Blue, Color::White, Color::Black]);
+    let (flags, feedback) = engine.submit_guess(guess).unwrap();
+    assert_eq!(flags, line(&[E, B, W, E]));
+    assert_eq!(feedback, feedback_from_flags(&flags));
+    assert_eq!(engine.gamestate.flag_pegs[0], flags);
+}
+
+#[test]
+fn clues_are_checked_against_the_marks() {
+    let mut engine = Engine::new(&config(GameMode::Practice, 3, 4, BotMode::Classic), None);
+    engine.gamestate.target_line = line(&[Color::Red, Color::Green, Color::White]);
+    engine
+        .submit_guess(line(&[Color::Red, Color::White, Color::Green]))
+        .unwrap();
+
+    // Same counts as the clue, but the exact peg is in another place
+    let contradiction = engine
+        .check_guess(&line(&[Color::Green, Color::White, Color::Red]))
+        .expect("the marks rule this code out");
+    assert_eq!(contradiction.guess_number, 1);
+    assert!(contradiction.marks.is_some());
+    assert!(
+        engine
+            .check_guess(&line(&[Color::Red, Color::Green, Color::White]))
+            .is_none()
+    );
+
+    // Only codes that would get the same marks are counted
+    let Some(Hint::Count(Some(count))) = engine.take_hint(HintKind::Count) else {
+        panic!("a 3x4 board can be counted");
+    };
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn bot_prunes_with_the_marks() {
+    let space = CodeSpace::new(Color::palette(4, false), 3);
+    let positional = space.clone().with_feedback_rule(FeedbackRule::Positional);
+    let target = line(&[Color::Red, Color::Green, Color::White]);
+    let guess = line(&[Color::Red, Color::White, Color::White]);
+    let flags = mark_positions(&target, &guess);
+
+    let mut marked = Bot::with_space(positional, BotSettings::default());
+    marked.observe_flags(&guess, &flags);
+    let mut counted = Bot::with_space(space.clone(), BotSettings::default());
+    counted.observe_feedback(&guess, score_guess(&target, &guess));
+
+    let expected: Vec<_> = space
+        .codes()
+        .into_iter()
+        .filter(|code| mark_positions(&space.unrank(*code), &guess) == flags)
+        .collect();
+    assert_eq!(marked.possible_solutions, expected);
+    assert!(marked.possible_solutions.len() < counted.possible_solutions.len());
+}
+
+#[test]
+fn bots_break_codes_with_marks() {
+    for mode in [
+        BotMode::Classic,
+        BotMode::Knuth,
+        BotMode::Optimal,
+        BotMode::Sampling,
+    ] {
+        let mut engine = Engine::new(&config(GameMode::SpectateBot, 3, 4, mode), None);
+        while !engine.gamestate.round_over {
+            engine.play_bot_turn().expect("bot guess was refused");
+        }
+        assert!(engine.round_status() == RoundStatus::Win, "{} lost", mode);
+    }
+
+    // The evil maker stays consistent with every mark it gave
+    let mut engine = Engine::new(
+        &GameConfig {
+            evil_maker: true,
+            ..config(GameMode::Practice, 3, 4, BotMode::Classic)
+        },
+        None,
+    );
+    for colors in [
+        [Color::White, Color::Black, Color::Red],
+        [Color::Red, Color::Red, Color::Green],
+    ] {
+        engine.submit_guess(line(&colors)).unwrap();
+    }
+    for (guess, flags) in engine
+        .gamestate
+        .guessed_lines
+        .iter()
+        .zip(&engine.gamestate.flag_pegs)
+    {
+        assert_eq!(mark_positions(&engine.gamestate.target_line, guess), *flags);
+    }
+}
+
+#[test]
+fn rule_limits_are_enforced() {
+    assert_eq!(
+        parse_feedback_rule("positional"),
+        Some(FeedbackRule::Positional)
+    );
+
+    let engine = Engine::new(&config(GameMode::Practice, 9, 2, BotMode::Classic), None);
+    assert_eq!(engine.gamestate.feedback_rule, FeedbackRule::Standard);
+
+    let engine = Engine::new(
+        &GameConfig {
+            human_scored: true,
+            ..config(GameMode::PlayerVsBot, 3, 4, BotMode::Knuth)
+        },
+        None,
+    );
+    assert_eq!(engine.gamestate.feedback_rule, FeedbackRule::Positional);
+    assert!(!engine.gamestate.human_scored);
+}
@@ -0,0 +1,53 @@
+// The sampling bot must take the bot seat for spaces too large to enumerate
+// and keep playing without blowing up, and still solve ordinary games.
+
+use mastermind::{
+    bot::{BotMode, BotSettings},
+    engine::Engine,
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::RoundStatus,
+    types::{GameMode, Line},
+};
+
+fn spectate_config(pegs: u8, colors: u8, mode: BotMode) -> GameConfig {
+    GameConfig {
+        game_mode: GameMode::SpectateBot,
+        number_of_guesses: 12,
+        pegs_in_a_line: pegs,
+        number_of_colors: colors,
+        is_empty_pegs_allowed: false,
+        bot_settings: BotSettings {
+            mode,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn huge_games_get_a_bot_that_keeps_playing() {
+    // 10^255 codes: enumerating would need more memory than exists.
+    let cfg = spectate_config(255, 10, BotMode::Classic);
+    let mut engine = Engine::new(&cfg, None);
+    for _ in 0..3 {
+        let (guess, _) = engine.play_bot_turn().expect("the bot plays a legal guess");
+        assert_eq!(guess.pegs.len(), 255);
+    }
+}
+
+#[test]
+fn sampling_bot_guesses_stay_consistent_and_solve_4x6() {
+    for _ in 0..20 {
+        let mut engine = Engine::new(&spectate_config(4, 6, BotMode::Sampling), None);
+        let mut history: Vec<(Line, _)> = Vec::new();
+        while engine.round_status() == RoundStatus::Ongoing {
+            let (guess, feedback) = engine.play_bot_turn().expect("the bot plays a legal guess");
+            for (earlier, earlier_feedback) in &history {
+                assert_eq!(score_guess(&guess, earlier), *earlier_feedback);
+            }
+            history.push((guess, feedback));
+        }
+        assert!(engine.round_status() == RoundStatus::Win);
+    }
+}
@@ -0,0 +1,96 @@
+// Property tests: the histogram scoring must agree exactly with check_for_matches.
+
+use mastermind::{
+    gamelogic::{check_for_matches, score_digits, score_guess},
+    types::{CodeSpace, Color, Line, MAX_COLORS},
+};
+
+use rand::Rng;
+
+const EXHAUSTIVE_LIMIT: u64 = 256;
+const RANDOM_PAIRS: usize = 2_000;
+
+fn assert_same_feedback(space: &CodeSpace, target: &Line, guess: &Line) {
+    let (_, expected) = check_for_matches(target, guess);
+    assert_eq!(
+        score_guess(target, guess),
+        expected,
+        "score_guess: target {} guess {}",
+        target,
+        guess
+    );
+
+    let target_digits: Vec<u8> = target
+        .pegs
+        .iter()
+        .map(|p| space.index_of(p.color).unwrap() as u8)
+        .collect();
+    let guess_digits: Vec<u8> = guess
+        .pegs
+        .iter()
+        .map(|p| space.index_of(p.color).unwrap() as u8)
+        .collect();
+    assert_eq!(
+        score_digits(&target_digits, &guess_digits),
+        expected,
+        "score_digits: target {} guess {}",
+        target,
+        guess
+    );
+}
+
+fn random_line(space: &CodeSpace, rng: &mut impl Rng) -> Line {
+    Line::new(
+        (0..space.width)
+            .map(|_| space.palette[rng.random_range(0..space.palette.len())])
+            .collect(),
+    )
+}
+
+#[test]
+fn histogram_scoring_matches_check_for_matches() {
+    let mut rng = rand::rng();
+
+    for width in 1..=8 {
+        for colors in 2..=MAX_COLORS {
+            for is_empty_allowed in [false, true] {
+                let space = CodeSpace::new(Color::palette(colors, is_empty_allowed), width);
+                let size = space.size().unwrap();
+
+                if size <= EXHAUSTIVE_LIMIT {
+                    for target in 0..size {
+                        for guess in 0..size {
+                            assert_same_feedback(
+                                &space,
+                                &space.unrank(target),
+                                &space.unrank(guess),
+                            );
+                        }
+                    }
+                } else {
+                    for _ in 0..RANDOM_PAIRS {
+                        let target = random_line(&space, &mut rng);
+                        let guess = random_line(&space, &mut rng);
+                        assert_same_feedback(&space, &target, &guess);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn histogram_scoring_matches_on_wide_lines() {
+    let mut rng = rand::rng();
+
+    for width in [16, 30, 64, 255] {
+        for colors in [2, 6, MAX_COLORS] {
+            let space = CodeSpace::new(Color::palette(colors, true), width);
+            for _ in 0..200 {
+                let target = random_line(&space, &mut rng);
+                let guess = random_line(&space, &mut rng);
+                assert_same_feedback(&space, &target, &guess);
+            }
+        }
+    }
+}
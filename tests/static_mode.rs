@@ -0,0 +1,149 @@
+// A static round takes a batch of guesses first and then exactly one final
+// guess; the bot's batch must separate every code so its final guess wins.
+
+use mastermind::{
+    bot::{Bot, BotMode, BotSettings, Difficulty},
+    engine::{Engine, GuessError},
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::RoundStatus,
+    strategy::SearchControl,
+    types::{CodeSpace, Color, GameMode, Line},
+};
+
+use std::collections::HashSet;
+
+fn config(game_mode: GameMode, pegs: u8, colors: u8) -> GameConfig {
+    GameConfig {
+        game_mode,
+        number_of_guesses: 6,
+        pegs_in_a_line: pegs,
+        number_of_colors: colors,
+        bot_settings: BotSettings {
+            mode: BotMode::Knuth,
+            ..Default::default()
+        },
+        static_mode: true,
+        ..Default::default()
+    }
+}
+
+const TARGET: [Color; 4] = [Color::Red, Color::Blue, Color::Green, Color::White];
+
+fn practice_with_target() -> Engine {
+    let mut engine = Engine::new(&config(GameMode::Practice, 4, 6), None);
+    engine.gamestate.target_line = Line::new(TARGET.to_vec());
+    engine
+}
+
+fn batch() -> Vec<Line> {
+    vec![
+        Line::new(vec![Color::Red, Color::Red, Color::Black, Color::Black]),
+        Line::new(vec![Color::Blue, Color::Green, Color::White, Color::Yellow]),
+    ]
+}
+
+#[test]
+fn the_batch_comes_first_and_only_once() {
+    let mut engine = practice_with_target();
+    assert_eq!(
+        engine.submit_guess(Line::new(TARGET.to_vec())),
+        Err(GuessError::BatchExpected)
+    );
+    assert_eq!(
+        engine.submit_batch(vec![Line::new(TARGET.to_vec()); 6]),
+        Err(GuessError::BatchSize { max: 5, found: 6 })
+    );
+    assert!(engine.gamestate.guessed_lines.is_empty());
+
+    let results = engine.submit_batch(batch()).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(engine.gamestate.batch_size, 2);
+    assert!(engine.round_status() == RoundStatus::Ongoing);
+    assert_eq!(
+        engine.submit_batch(batch()),
+        Err(GuessError::NoBatchExpected)
+    );
+}
+
+#[test]
+fn the_round_ends_with_the_final_guess() {
+    let mut engine = practice_with_target();
+    engine.submit_batch(batch()).unwrap();
+    let wrong = Line::new(vec![Color::White, Color::Blue, Color::Green, Color::Red]);
+    engine.submit_guess(wrong).unwrap();
+    assert!(engine.round_status() == RoundStatus::Loss);
+    assert_eq!(engine.gamestate.guessed_lines.len(), 3);
+
+    let mut engine = practice_with_target();
+    engine.submit_batch(batch()).unwrap();
+    engine.submit_guess(Line::new(TARGET.to_vec())).unwrap();
+    assert!(engine.round_status() == RoundStatus::Win);
+}
+
+#[test]
+fn bot_batch_separates_every_code() {
+    let settings = BotSettings {
+        mode: BotMode::Knuth,
+        ..Default::default()
+    };
+    let mut bot = Bot::new(Color::palette(4, false), 3, settings);
+    let batch = bot.next_batch_within(8, &SearchControl::unlimited());
+    assert!(batch.len() <= 8);
+
+    let space = CodeSpace::new(Color::palette(4, false), 3);
+    let answers: HashSet<Vec<_>> = space
+        .codes()
+        .iter()
+        .map(|code| {
+            let target = space.unrank(*code);
+            batch
+                .iter()
+                .map(|guess| score_guess(&target, guess))
+                .collect()
+        })
+        .collect();
+    assert_eq!(answers.len() as u64, space.size().unwrap());
+}
+
+#[test]
+fn casual_batches_record_each_guess_once() {
+    for difficulty in [Difficulty::Consistent, Difficulty::Greedy] {
+        let settings = BotSettings {
+            difficulty,
+            ..Default::default()
+        };
+        let mut bot = Bot::new(Color::palette(4, false), 3, settings);
+        let batch = bot.next_batch_within(8, &SearchControl::unlimited());
+        let distinct: HashSet<&Line> = batch.iter().collect();
+        assert_eq!(distinct.len(), batch.len());
+        assert_eq!(bot.guessed_codes.len(), batch.len());
+    }
+}
+
+#[test]
+fn spectated_bot_wins_every_static_round() {
+    let mut engine = Engine::new(&config(GameMode::SpectateBot, 3, 4), None);
+    for _ in 0..3 {
+        let batch = engine.play_bot_batch().expect("bot batch was refused");
+        assert!(batch.len() <= engine.max_batch_size());
+        // A batch guess that hits the code already wins the round
+        if !engine.gamestate.round_over {
+            engine.play_bot_turn().expect("final guess was refused");
+        }
+        assert!(engine.round_status() == RoundStatus::Win);
+        engine.finish_round();
+        engine.start_next_round();
+    }
+}
+
+#[test]
+fn static_games_are_never_human_scored() {
+    let cfg = GameConfig {
+        human_scored: true,
+        ..config(GameMode::PlayerVsBot, 3, 4)
+    };
+    let engine = Engine::new(&cfg, None);
+    assert!(engine.gamestate.static_mode);
+    assert!(!engine.gamestate.human_scored);
+}
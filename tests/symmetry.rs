@@ -0,0 +1,103 @@
+// Symmetries::representatives must return exactly the lowest code of every
+// class of codes that are images of each other under a color relabeling plus
+// position reordering that leaves all guesses so far unchanged.
+
+use mastermind::{
+    symmetry::Symmetries,
+    types::{Code, CodeSpace, Color},
+};
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for rest in permutations(n - 1) {
+        for i in 0..n {
+            let mut permutation = rest.clone();
+            permutation.insert(i, n - 1);
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+fn apply(digits: &[u8], positions: &[usize], colors: &[usize]) -> Vec<u8> {
+    positions
+        .iter()
+        .map(|p| colors[digits[*p] as usize] as u8)
+        .collect()
+}
+
+fn to_code(space: &CodeSpace, digits: &[u8]) -> Code {
+    digits
+        .iter()
+        .fold(0, |code, d| code * space.base() + *d as Code)
+}
+
+// Lowest code of every class, by trying every position and color permutation.
+fn brute_force_representatives(space: &CodeSpace, guesses: &[Code]) -> Vec<Code> {
+    let width = space.width;
+    let digits_of = |code: Code| {
+        let mut digits = vec![0; width];
+        space.digits(code, &mut digits);
+        digits
+    };
+    let guess_digits: Vec<Vec<u8>> = guesses.iter().map(|g| digits_of(*g)).collect();
+
+    let mut group = Vec::new();
+    for positions in permutations(width) {
+        for colors in permutations(space.base() as usize) {
+            if guess_digits
+                .iter()
+                .all(|g| apply(g, &positions, &colors) == *g)
+            {
+                group.push((positions.clone(), colors));
+            }
+        }
+    }
+
+    (0..space.size().unwrap())
+        .filter(|code| {
+            let digits = digits_of(*code);
+            group.iter().all(|(positions, colors)| {
+                to_code(space, &apply(&digits, positions, colors)) >= *code
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn representatives_are_the_lowest_code_of_each_class() {
+    let space = CodeSpace::new(Color::palette(4, false), 4);
+    let code = |digits: &[u8]| to_code(&space, digits);
+    let games: Vec<Vec<Code>> = vec![
+        vec![],
+        vec![code(&[0, 0, 1, 1])],
+        vec![code(&[0, 0, 1, 2])],
+        vec![code(&[0, 0, 1, 1]), code(&[0, 1, 2, 3])],
+        vec![code(&[0, 1, 2, 3]), code(&[1, 0, 3, 2])],
+    ];
+
+    for guesses in games {
+        let symmetries = guesses
+            .iter()
+            .fold(Symmetries::new(&space), |symmetries, guess| {
+                symmetries.after_guess(*guess)
+            });
+        assert_eq!(
+            symmetries.representatives(0..space.size().unwrap()),
+            brute_force_representatives(&space, &guesses),
+            "after guesses {:?}",
+            guesses
+        );
+    }
+}
+
+#[test]
+fn five_openings_on_4x6() {
+    let space = CodeSpace::new(Color::palette(6, false), 4);
+    let openings = Symmetries::new(&space).representatives(0..space.size().unwrap());
+    // 1111, 1112, 1122, 1123 and 1234.
+    assert_eq!(openings.len(), 5);
+}
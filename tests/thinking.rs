@@ -0,0 +1,130 @@
+// Every bot must honour its time limit and a cancel request by still playing
+// a legal guess, report progress, and run in a capped thread pool.
+
+use mastermind::{
+    bot::{BotMode, BotSettings},
+    engine::Engine,
+    gameconfig::{GameConfig, parse_time_limit},
+    gamelogic::score_guess,
+    gamestate::RoundStatus,
+    strategy::SearchControl,
+    types::{GameMode, Line},
+};
+
+use std::time::{Duration, Instant};
+
+fn spectate_config(pegs: u8, colors: u8, bot_settings: BotSettings) -> GameConfig {
+    GameConfig {
+        game_mode: GameMode::SpectateBot,
+        number_of_guesses: 12,
+        pegs_in_a_line: pegs,
+        number_of_colors: colors,
+        is_empty_pegs_allowed: false,
+        bot_settings,
+        ..Default::default()
+    }
+}
+
+fn settings(mode: BotMode) -> BotSettings {
+    BotSettings {
+        mode,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn time_limit_cuts_a_long_search_short() {
+    // The second Classic guess on 6x8 scores tens of thousands of candidates
+    // against each other, which takes far longer than the limit.
+    let bot_settings = BotSettings {
+        time_limit_ms: Some(200),
+        ..settings(BotMode::Classic)
+    };
+    let mut engine = Engine::new(&spectate_config(6, 8, bot_settings), None);
+    for _ in 0..2 {
+        let started = Instant::now();
+        let (guess, _) = engine.play_bot_turn().expect("the bot plays a legal guess");
+        assert_eq!(guess.pegs.len(), 6);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
+
+#[test]
+fn cancelled_bots_still_play_consistent_guesses() {
+    for mode in [BotMode::Classic, BotMode::Knuth] {
+        let mut engine = Engine::new(&spectate_config(4, 6, settings(mode)), None);
+        let mut history: Vec<(Line, _)> = Vec::new();
+        for _ in 0..3 {
+            if engine.round_status() != RoundStatus::Ongoing {
+                break;
+            }
+            let control = SearchControl::unlimited();
+            control.cancel();
+            let (guess, feedback) = engine
+                .play_bot_turn_within(&control)
+                .expect("the bot plays a legal guess");
+            for (earlier, earlier_feedback) in &history {
+                assert_eq!(score_guess(&guess, earlier), *earlier_feedback);
+            }
+            history.push((guess, feedback));
+        }
+    }
+}
+
+#[test]
+fn optimal_search_stops_on_time() {
+    // The Optimal opening on 4x6 is a search of several seconds.
+    let bot_settings = BotSettings {
+        time_limit_ms: Some(200),
+        ..settings(BotMode::Optimal)
+    };
+    let mut engine = Engine::new(&spectate_config(4, 6, bot_settings), None);
+    let started = Instant::now();
+    let (guess, _) = engine.play_bot_turn().expect("the bot plays a legal guess");
+    assert_eq!(guess.pegs.len(), 4);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn cancelled_sampling_bot_keeps_playing_huge_games() {
+    let mut engine = Engine::new(&spectate_config(255, 10, settings(BotMode::Classic)), None);
+    let control = SearchControl::unlimited();
+    control.cancel();
+    let (guess, _) = engine
+        .play_bot_turn_within(&control)
+        .expect("the bot plays a legal guess");
+    assert_eq!(guess.pegs.len(), 255);
+}
+
+#[test]
+fn progress_reaches_the_total() {
+    let mut engine = Engine::new(&spectate_config(4, 6, settings(BotMode::Knuth)), None);
+    let control = SearchControl::unlimited();
+    engine.play_bot_turn_within(&control);
+    let (done, total) = control.progress();
+    assert_eq!(total, 1296);
+    assert_eq!(done, total);
+}
+
+#[test]
+fn capped_thread_pool_still_solves_4x6() {
+    let bot_settings = BotSettings {
+        threads: Some(1),
+        ..settings(BotMode::Knuth)
+    };
+    let mut engine = Engine::new(&spectate_config(4, 6, bot_settings), None);
+    while engine.round_status() == RoundStatus::Ongoing {
+        engine.play_bot_turn().expect("the bot plays a legal guess");
+    }
+    assert!(engine.round_status() == RoundStatus::Win);
+}
+
+#[test]
+fn time_limits_parse_from_seconds() {
+    assert_eq!(parse_time_limit("2.5"), Some(Some(2500)));
+    assert_eq!(parse_time_limit("10"), Some(Some(10_000)));
+    assert_eq!(parse_time_limit("0"), Some(None));
+    assert_eq!(parse_time_limit("none"), Some(None));
+    assert_eq!(parse_time_limit("-1"), None);
+    assert_eq!(parse_time_limit("soon"), None);
+}
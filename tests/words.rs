@@ -0,0 +1,266 @@
+// The word variant: codes and guesses are words from a dictionary, scored
+// with black and white pegs over letters, and the bot only ever guesses words.
+
+use mastermind::{
+    bot::{BotMode, Difficulty},
+    engine::{Engine, GuessError},
+    gameconfig::GameConfig,
+    gamelogic::score_guess,
+    gamestate::RoundStatus,
+    hints::{HintKind, hint_command},
+    types::{Color, GameMode, Line},
+    words::{Dictionary, DictionaryError, WordError},
+};
+
+mod common;
+use common::feedback;
+
+use std::{fs, sync::Arc};
+
+const WORDS: &[&str] = &[
+    "crane", "slate", "trace", "crate", "react", "caret", "cater", "least", "steal", "tales",
+    "stale", "plant", "grant", "giant", "faint", "paint", "saint", "taint", "joint", "point",
+];
+
+fn dictionary() -> Dictionary {
+    Dictionary::from_words(WORDS.iter().copied(), 5).unwrap()
+}
+
+// A word list file of WORDS, named after the test so tests don't share one.
+fn word_list(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("mastermind-words-{}.txt", name));
+    fs::write(&path, format!("# test words\n\n{}\n", WORDS.join("\n"))).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn config(game_mode: GameMode, mode: BotMode, word_list: &str) -> GameConfig {
+    GameConfig {
+        word_list: Some(word_list.to_string()),
+        ..common::config(game_mode, 5, 6, mode)
+    }
+}
+
+// The Engine keeps the words it read, so the file can go right away.
+fn engine(name: &str, game_mode: GameMode, mode: BotMode) -> Engine {
+    let path = word_list(name);
+    let engine = Engine::new(&config(game_mode, mode, &path), None);
+    fs::remove_file(&path).ok();
+    engine
+}
+
+#[test]
+fn dictionary_keeps_words_of_the_length() {
+    let two_words =
+        Dictionary::from_words(["Crane", "crane", " slate ", "cranes", "it's", "abc"], 5).unwrap();
+    assert_eq!(two_words.len(), 2);
+    assert_eq!(two_words.word(0), "crane");
+    assert_eq!(two_words.word(1), "slate");
+
+    assert!(matches!(
+        Dictionary::from_words(["crane"], 4),
+        Err(DictionaryError::NoWords { length: 4 })
+    ));
+    let long_word = "a".repeat(65);
+    assert_eq!(
+        Dictionary::from_words([long_word.as_str()], 65),
+        Err(DictionaryError::BadLength { length: 65 })
+    );
+    assert_eq!(
+        Dictionary::load("no/such/word/list.txt", 0),
+        Err(DictionaryError::BadLength { length: 0 })
+    );
+    assert!(matches!(
+        Dictionary::load("no/such/word/list.txt", 5),
+        Err(DictionaryError::Unreadable { .. })
+    ));
+
+    // Blank lines and comments are skipped
+    let path = word_list("load");
+    let loaded = Dictionary::load(&path, 5);
+    fs::remove_file(&path).ok();
+    assert_eq!(loaded, Ok(dictionary()));
+}
+
+#[test]
+fn guesses_must_be_words() {
+    let dictionary = dictionary();
+    assert_eq!(dictionary.word(dictionary.find("CRANE").unwrap()), "crane");
+    assert_eq!(
+        dictionary.find("cran"),
+        Err(WordError::WrongLength {
+            expected: 5,
+            found: 4
+        })
+    );
+    assert_eq!(dictionary.find("cr4ne"), Err(WordError::NotALetter('4')));
+    assert_eq!(
+        dictionary.find("zzzzz"),
+        Err(WordError::UnknownWord("zzzzz".to_string()))
+    );
+
+    let mut engine = engine("guesses", GameMode::Practice, BotMode::Classic);
+    assert!(engine.gamestate.is_word_game());
+    let not_a_word = Line::new(vec![Color::Letter(25); 5]);
+    assert_eq!(
+        engine.submit_guess(not_a_word),
+        Err(GuessError::NotAWord("zzzzz".to_string()))
+    );
+    assert_eq!(
+        engine.submit_guess(Line::new(vec![Color::Red; 5])),
+        Err(GuessError::ColorNotInPlay(Color::Red))
+    );
+    let crane = dictionary.line(dictionary.find("crane").unwrap());
+    assert!(engine.submit_guess(crane).is_ok());
+}
+
+#[test]
+fn letters_score_like_colors() {
+    let dictionary = dictionary();
+    let word = |w: &str| dictionary.line(dictionary.find(w).unwrap());
+    assert_eq!(score_guess(&word("crane"), &word("crane")), feedback(5, 0));
+    // c, r, a and e in place; no n in crate
+    assert_eq!(score_guess(&word("crate"), &word("crane")), feedback(4, 0));
+    assert_eq!(score_guess(&word("react"), &word("crate")), feedback(1, 4));
+    assert_eq!(score_guess(&word("joint"), &word("slate")), feedback(0, 1));
+}
+
+#[test]
+fn bot_breaks_words_with_dictionary_guesses() {
+    let dictionary = dictionary();
+    for mode in [BotMode::Classic, BotMode::Knuth] {
+        let mut engine = engine("bot", GameMode::SpectateBot, mode);
+        while engine.round_status() == RoundStatus::Ongoing {
+            engine.play_bot_turn().expect("it is the bot's turn");
+        }
+        assert!(engine.round_status() == RoundStatus::Win, "{} lost", mode);
+        assert!(engine.gamestate.guessed_lines.len() <= 6);
+        for guess in &engine.gamestate.guessed_lines {
+            assert!(
+                dictionary.index_of_line(guess).is_some(),
+                "{} guessed {}",
+                mode,
+                guess
+            );
+        }
+    }
+
+    // Random guesses are words too
+    let path = word_list("random");
+    let mut cfg = config(GameMode::SpectateBot, BotMode::Classic, &path);
+    cfg.bot_settings.difficulty = Difficulty::Random;
+    let mut engine = Engine::new(&cfg, None);
+    fs::remove_file(&path).ok();
+    let (guess, _) = engine.play_bot_turn().unwrap();
+    assert!(dictionary.index_of_line(&guess).is_some());
+}
+
+#[test]
+fn word_rounds_are_played_and_scored() {
+    let dictionary = dictionary();
+    let mut engine = engine("rounds", GameMode::PlayerVsBot, BotMode::Knuth);
+    assert!(!engine.is_bots_turn());
+    let target = engine.gamestate.target_line.clone();
+    assert!(dictionary.index_of_line(&target).is_some());
+    let crane = dictionary.line(dictionary.find("crane").unwrap());
+    let slate = dictionary.line(dictionary.find("slate").unwrap());
+    let other = if target == crane { slate } else { crane };
+    assert!(engine.submit_guess(other).unwrap().1.correct_position < 5);
+    assert_eq!(engine.submit_guess(target).unwrap().1, feedback(5, 0));
+    let result = engine.finish_round();
+    assert!(result.is_win);
+    assert_eq!(engine.gamestate.p2_score, 2);
+
+    // The human picks the bot's word
+    engine.start_next_round();
+    assert!(engine.is_bots_turn());
+    assert!(engine.needs_human_target());
+    engine.set_target(dictionary.line(dictionary.find("point").unwrap()));
+    while engine.round_status() == RoundStatus::Ongoing {
+        engine.play_bot_turn().unwrap();
+    }
+    assert!(engine.round_status() == RoundStatus::Win);
+    engine.finish_round();
+    assert_eq!(
+        engine.gamestate.p1_score,
+        engine.gamestate.guessed_lines.len() as u8
+    );
+}
+
+#[test]
+fn optimal_plays_as_knuth_in_word_games() {
+    let engine = engine("optimal", GameMode::SpectateBot, BotMode::Optimal);
+    assert_eq!(engine.gamestate.bot_settings.mode, BotMode::Knuth);
+
+    let path = word_list("optimal-config");
+    let config_path = std::env::temp_dir().join("mastermind-words-optimal-config.cfg");
+    fs::write(
+        &config_path,
+        format!("pegs_in_a_line=5\nbot_mode=optimal\nword_list={}\n", path),
+    )
+    .unwrap();
+    let loaded = GameConfig::load_from_file(config_path.to_str().unwrap());
+    fs::remove_file(&config_path).ok();
+    fs::remove_file(&path).ok();
+    let (cfg, warnings) = loaded.expect("the file exists");
+    assert_eq!(cfg.bot_settings.mode, BotMode::Knuth);
+    assert_eq!(
+        warnings,
+        vec!["Word games have no Optimal bot; playing as Knuth.".to_string()]
+    );
+}
+
+#[test]
+fn unreadable_word_lists_play_with_colors() {
+    let engine = Engine::new(
+        &config(
+            GameMode::Practice,
+            BotMode::Classic,
+            "no/such/word/list.txt",
+        ),
+        None,
+    );
+    assert!(!engine.gamestate.is_word_game());
+    assert_eq!(engine.gamestate.word_list, None);
+    assert_eq!(engine.gamestate.palette().len(), 6);
+}
+
+#[test]
+fn guesses_wait_for_the_makers_word() {
+    let dictionary = dictionary();
+    let crane = dictionary.line(dictionary.find("crane").unwrap());
+    let mut engine = engine("no-target", GameMode::TwoPlayer, BotMode::Classic);
+    assert!(engine.needs_human_target());
+    assert_eq!(
+        engine.submit_guess(crane.clone()),
+        Err(GuessError::NoTarget)
+    );
+    assert_eq!(engine.take_hint(HintKind::RevealPeg), None);
+    assert!(engine.gamestate.guessed_lines.is_empty());
+
+    engine.set_target(crane.clone());
+    assert_eq!(engine.submit_guess(crane).unwrap().1, feedback(5, 0));
+
+    // The next round's word isn't the last one
+    engine.finish_round();
+    engine.start_next_round();
+    let point = dictionary.line(dictionary.find("point").unwrap());
+    assert_eq!(engine.submit_guess(point), Err(GuessError::NoTarget));
+}
+
+#[test]
+fn words_starting_with_hint_are_guesses() {
+    let mut engine = engine("hint-words", GameMode::Practice, BotMode::Classic);
+    let five_letters = Dictionary::from_words(["hints", "crane"], 5).unwrap();
+    engine.gamestate.dictionary = Some(Arc::new(five_letters));
+    assert_eq!(hint_command(&engine.gamestate, "hints"), None);
+    assert_eq!(hint_command(&engine.gamestate, "hint s"), Some("s"));
+    assert_eq!(hint_command(&engine.gamestate, "hint"), Some(""));
+
+    // A word spelled "hint" is played, not asked for
+    let four_letters = Dictionary::from_words(["hint", "mint"], 4).unwrap();
+    engine.gamestate.pegs_in_a_line = 4;
+    engine.gamestate.dictionary = Some(Arc::new(four_letters));
+    assert_eq!(hint_command(&engine.gamestate, "hint"), None);
+    assert_eq!(hint_command(&engine.gamestate, "hinted"), None);
+    assert_eq!(hint_command(&engine.gamestate, "hint count"), Some("count"));
+}